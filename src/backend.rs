@@ -0,0 +1,132 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use crate::config::JenkinsInstanceConfig;
+use crate::jenkins::JenkinsClient;
+
+/// Opaque reference to a specific build on whatever CI system backs a
+/// `CIBackend`. Jenkins happens to resolve this from its job API response,
+/// but callers must not assume any particular shape or try to parse it.
+#[derive(Debug, Clone)]
+pub struct BuildHandle(pub(crate) BuildDetails);
+
+/// The subset of a build's metadata the scheduler needs, independent of
+/// which CI system produced it.
+#[derive(Debug, Clone)]
+pub struct BuildDetails {
+    pub number: u64,
+    pub timestamp: DateTime<Utc>,
+    pub result: Option<String>,
+    /// Build runtime in milliseconds, when the backend can report one.
+    pub duration_millis: Option<i64>,
+}
+
+impl BuildDetails {
+    /// Build runtime in whole minutes, for comparing against
+    /// `max_build_duration_minutes`.
+    pub fn duration_minutes(&self) -> Option<i64> {
+        self.duration_millis.map(|millis| millis / 60_000)
+    }
+}
+
+/// A CI system capable of reporting a job's most recent build and its
+/// configured schedule. `Monitor::check_job` is written entirely against
+/// this trait so alternative backends (GitLab CI, GitHub Actions) can be
+/// added later without rewriting the scheduling/threshold logic.
+#[async_trait]
+pub trait CIBackend: Send + Sync {
+    /// Confirm the backend is reachable, for use at startup and by the
+    /// `test-connection` CLI command.
+    async fn test_connection(&self) -> Result<()>;
+
+    /// Look up a handle to the most recent build of `job`, if any has run.
+    async fn last_build_handle(&self, job: &str) -> Result<Option<BuildHandle>>;
+
+    /// Resolve the details of a specific build. For Jenkins this is free
+    /// (the handle already carries them), but other backends may need a
+    /// follow-up API call here.
+    async fn build_details(&self, handle: &BuildHandle) -> Result<BuildDetails>;
+
+    /// Look up the job's own schedule, if the backend can report one (e.g.
+    /// parsed from a Jenkins `config.xml`). Returns `None` when the backend
+    /// has no opinion and the configured `schedule` should be used as-is.
+    async fn job_schedule(&self, job: &str) -> Result<Option<String>>;
+
+    /// Fetch the full console log text for a specific build, for archiving
+    /// alongside a quality-gate alert.
+    async fn console_log(&self, job: &str, build_number: u64) -> Result<String>;
+
+    /// The human-facing URL for a build's console output, for inclusion in
+    /// alert bodies.
+    fn console_url(&self, job: &str, build_number: u64) -> String;
+}
+
+/// `CIBackend` implementation backed by a single Jenkins instance.
+pub struct JenkinsBackend {
+    client: JenkinsClient,
+}
+
+impl JenkinsBackend {
+    pub fn new(client: JenkinsClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl CIBackend for JenkinsBackend {
+    async fn test_connection(&self) -> Result<()> {
+        self.client.test_connection().await
+    }
+
+    async fn last_build_handle(&self, job: &str) -> Result<Option<BuildHandle>> {
+        let last_build = self.client.get_last_build(job).await?;
+        Ok(last_build.map(|build| {
+            BuildHandle(BuildDetails {
+                number: build.number,
+                timestamp: build.timestamp,
+                result: build.result,
+                duration_millis: build.duration_millis,
+            })
+        }))
+    }
+
+    async fn build_details(&self, handle: &BuildHandle) -> Result<BuildDetails> {
+        Ok(handle.0.clone())
+    }
+
+    async fn job_schedule(&self, job: &str) -> Result<Option<String>> {
+        let config_xml = self.client.get_config_xml(job).await?;
+        Ok(crate::jenkins::extract_schedule_from_config_xml(&config_xml))
+    }
+
+    async fn console_log(&self, job: &str, build_number: u64) -> Result<String> {
+        self.client.get_console_text(job, build_number).await
+    }
+
+    fn console_url(&self, job: &str, build_number: u64) -> String {
+        self.client.console_url(job, build_number)
+    }
+}
+
+/// Build one `JenkinsBackend` per configured `[[jenkins]]` instance, keyed
+/// by instance name, so `Monitor` can dispatch each job to the instance it
+/// belongs to. Each backend is wrapped in a `CachingBackend` so repeated
+/// `job_schedule` lookups within `schedule_cache_ttl` don't hit the network.
+pub fn build_backends(
+    instances: &[JenkinsInstanceConfig],
+    schedule_cache_ttl: std::time::Duration,
+) -> Result<HashMap<String, Box<dyn CIBackend>>> {
+    instances
+        .iter()
+        .map(|instance| {
+            let client = JenkinsClient::new(instance)
+                .with_context(|| format!("Failed to initialize Jenkins instance '{}'", instance.name))?;
+            let jenkins_backend: Box<dyn CIBackend> = Box::new(JenkinsBackend::new(client));
+            let cached_backend: Box<dyn CIBackend> =
+                Box::new(crate::schedule_cache::CachingBackend::new(jenkins_backend, schedule_cache_ttl));
+            Ok((instance.name.clone(), cached_backend))
+        })
+        .collect()
+}
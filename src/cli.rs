@@ -0,0 +1,26 @@
+use clap::{Parser, Subcommand};
+
+#[derive(Parser, Debug)]
+#[command(name = "jenkins-monitor", about = "Watch Jenkins jobs for missed or failed runs")]
+pub struct Cli {
+    /// Path to the config.toml to load
+    #[arg(long, global = true, default_value = "config.toml")]
+    pub config: String,
+
+    #[command(subcommand)]
+    pub command: Commands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Run the monitor continuously (the default long-running behavior)
+    Run,
+    /// Check connectivity to every configured Jenkins instance and exit
+    TestConnection,
+    /// Run a single monitoring pass over all jobs and print their status
+    CheckOnce,
+    /// Send a test alert through every configured notifier
+    SendTestAlert,
+    /// List configured jobs and their most recent expected run time
+    ListJobs,
+}
@@ -1,42 +1,197 @@
+use anyhow::{Context, Result};
+use chrono_tz::Tz;
 use serde::Deserialize;
 use std::fs;
+use std::str::FromStr;
+
+use crate::notifier::Severity;
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct Config {
     pub general: ConfigGeneral,
-    pub jenkins: ConfigJenkins,
-    pub job: Vec<ConfigJob>,
-    pub email: Option<ConfigEmail>,
+    #[serde(rename = "jenkins")]
+    pub jenkins_instances: Vec<JenkinsInstanceConfig>,
+    pub job: Vec<JobConfig>,
+    #[serde(rename = "notifier", default)]
+    pub notifiers: Vec<NotifierConfig>,
+    pub webhook: Option<WebhookServerConfig>,
+    pub console_archive: Option<ConsoleArchiveConfig>,
+}
+
+impl Config {
+    /// Resolve the Jenkins instance a job should be checked against: the
+    /// job's explicit `instance` name if set, otherwise the instance marked
+    /// `default = true`, otherwise the sole configured instance.
+    pub fn resolve_instance(&self, name: Option<&str>) -> Result<&JenkinsInstanceConfig> {
+        if let Some(name) = name {
+            return self
+                .jenkins_instances
+                .iter()
+                .find(|instance| instance.name == name)
+                .with_context(|| format!("job references unknown Jenkins instance '{}'", name));
+        }
+
+        if let Some(default) = self.jenkins_instances.iter().find(|instance| instance.default) {
+            return Ok(default);
+        }
+
+        match self.jenkins_instances.as_slice() {
+            [only] => Ok(only),
+            [] => anyhow::bail!("no Jenkins instances configured"),
+            _ => anyhow::bail!(
+                "multiple Jenkins instances configured but none marked `default = true` and no instance specified"
+            ),
+        }
+    }
+
+    /// Resolve the IANA timezone a job's cron schedule should be evaluated
+    /// in: the job's explicit `timezone`, otherwise `[general].default_timezone`,
+    /// otherwise UTC. A local-time Jenkins schedule (e.g. a nightly cron
+    /// meant to fire at 2am Europe/Berlin) would otherwise be misread
+    /// against `Utc::now()` and misfire around DST transitions.
+    pub fn resolve_timezone(&self, job: &JobConfig) -> Result<Tz> {
+        let name = job
+            .timezone
+            .as_deref()
+            .or(self.general.default_timezone.as_deref())
+            .unwrap_or("UTC");
+
+        Tz::from_str(name).map_err(|e| anyhow::anyhow!("invalid timezone '{}': {}", name, e))
+    }
+
+    /// Resolve how long to suppress repeat alerts for a job: its own
+    /// `reminder_interval_minutes`, otherwise `[general].default_reminder_interval_minutes`,
+    /// otherwise 60.
+    pub fn resolve_reminder_interval_minutes(&self, job: &JobConfig) -> i64 {
+        job.reminder_interval_minutes
+            .or(self.general.default_reminder_interval_minutes)
+            .unwrap_or(60)
+    }
+
+    /// Resolve the working-hours window alerts for a job should be confined
+    /// to, if any: its own `working_hours`, otherwise
+    /// `[general].default_working_hours`.
+    pub fn resolve_working_hours<'a>(&'a self, job: &'a JobConfig) -> Option<&'a str> {
+        job.working_hours
+            .as_deref()
+            .or(self.general.default_working_hours.as_deref())
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct ConfigGeneral {
     pub log_level: String,
-    #[serde(default = "default_check_interval")]
-    pub check_interval_seconds: u64,
+    /// Floor on how often the scheduler will wake up to run due checks, so a
+    /// tight cluster of per-job deadlines can't turn into a busy loop. Does
+    /// not delay a job whose next deadline is further out than this.
+    #[serde(default = "default_min_poll_interval_seconds")]
+    pub min_poll_interval_seconds: u64,
+    /// Maximum number of jobs checked concurrently per cycle, so a cycle
+    /// completes in roughly the time of the slowest single check instead of
+    /// the sum of all of them, while still capping how many simultaneous
+    /// requests hit the Jenkins controller. Defaults to 4.
+    #[serde(default = "default_max_concurrent_checks")]
+    pub max_concurrent_checks: usize,
     #[serde(default = "default_alert_on_check_error")]
     pub alert_on_check_error: bool,
+    #[serde(default = "default_db_path")]
+    pub db_path: String,
+    /// How long a backend-reported job schedule may be reused before it's
+    /// refetched. Only relevant to backends whose `job_schedule` does real
+    /// I/O (e.g. fetching a Jenkins `config.xml`).
+    #[serde(default = "default_schedule_cache_ttl_seconds")]
+    pub schedule_cache_ttl_seconds: u64,
+    /// IANA timezone (e.g. `"America/New_York"`) jobs without their own
+    /// `timezone` fall back to when evaluating their cron schedule. Defaults
+    /// to UTC.
+    pub default_timezone: Option<String>,
+    /// Minutes to suppress repeat alerts for a job before re-sending, unless
+    /// overridden per-job. Defaults to 60.
+    pub default_reminder_interval_minutes: Option<i64>,
+    /// Working-hours window (e.g. `"09:00-18:00"`, Mon-Fri in the job's
+    /// resolved timezone) jobs without their own `working_hours` fall back
+    /// to. Unset means alerts are delivered at any time.
+    pub default_working_hours: Option<String>,
 }
 
-fn default_check_interval() -> u64 {
-    60
+fn default_min_poll_interval_seconds() -> u64 {
+    10
+}
+
+fn default_max_concurrent_checks() -> usize {
+    4
+}
+
+fn default_db_path() -> String {
+    "./state.db".to_string()
 }
 
+fn default_schedule_cache_ttl_seconds() -> u64 {
+    900
+}
+
+/// One monitored Jenkins controller. Most setups only need a single
+/// `[[jenkins]]` entry, but naming them lets one daemon watch several
+/// servers (e.g. staging and production) at once.
 #[derive(Deserialize, Debug, Clone)]
-pub struct ConfigJenkins {
+pub struct JenkinsInstanceConfig {
+    pub name: String,
     pub url: String,
     pub username: String,
     pub password: String,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Instance jobs fall back to when they don't set `instance` explicitly.
+    /// Only meaningful (and only required) when more than one instance is
+    /// configured.
+    #[serde(default)]
+    pub default: bool,
+}
+
+fn default_timeout_secs() -> u64 {
+    30
 }
 
 #[derive(Deserialize, Debug, Clone)]
-pub struct ConfigJob {
+pub struct JobConfig {
     pub name: String,
     pub schedule: String,
     #[serde(default = "default_alert_threshold")]
     pub alert_threshold_minutes: i64,
     // Optional per-job override of whether to alert when check_job() returns an error
     pub alert_on_error: Option<bool>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Name of the `[[jenkins]]` instance this job lives on. Falls back to
+    /// the configured default instance (or the sole instance) when unset.
+    pub instance: Option<String>,
+    /// Build results that should trigger an alert even when the job ran on
+    /// schedule, e.g. `["FAILURE", "UNSTABLE", "ABORTED"]`.
+    #[serde(default)]
+    pub alert_on_result: Vec<String>,
+    /// IANA timezone this job's `schedule` should be evaluated in (e.g. a
+    /// Jenkins `config.xml` cron meant to fire at 2am local time). Falls
+    /// back to `[general].default_timezone`, then UTC.
+    pub timezone: Option<String>,
+    /// Daily maintenance window the last build must have finished within,
+    /// e.g. `"01:30-02:30"` (in the job's resolved timezone). Independent
+    /// of the cron-staleness check: a job can run on schedule yet still
+    /// finish outside the window it's required to complete in.
+    pub daily_window: Option<String>,
+    /// Alert if the most recent build ran longer than this many minutes,
+    /// catching hung or degraded jobs that still report success.
+    pub max_build_duration_minutes: Option<i64>,
+    /// Minutes to suppress repeat alerts once one has been sent, so a
+    /// broken nightly job doesn't spam an identical email every time the
+    /// scheduler re-checks it. Falls back to
+    /// `[general].default_reminder_interval_minutes`, then 60.
+    pub reminder_interval_minutes: Option<i64>,
+    /// Only deliver alerts for this job during this window (e.g.
+    /// `"09:00-18:00"`, Mon-Fri in the job's resolved timezone); alerts
+    /// raised outside it are deferred rather than dropped, and are sent by
+    /// the first check of the job that runs inside the window. Falls back
+    /// to `[general].default_working_hours`, then unrestricted.
+    pub working_hours: Option<String>,
 }
 
 fn default_alert_threshold() -> i64 {
@@ -47,8 +202,22 @@ fn default_alert_on_check_error() -> bool {
     true
 }
 
+fn default_enabled() -> bool {
+    true
+}
+
+/// One configured alert destination. Tagged by `kind` so a `config.toml` can
+/// list several `[[notifier]]` entries of different kinds at once.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NotifierConfig {
+    Email(EmailConfig),
+    Webhook(WebhookConfig),
+    Desktop(DesktopConfig),
+}
+
 #[derive(Deserialize, Debug, Clone)]
-pub struct ConfigEmail {
+pub struct EmailConfig {
     pub smtp_host: String,
     pub smtp_port: u16,
     #[serde(default = "default_smtp_tls")]
@@ -57,18 +226,67 @@ pub struct ConfigEmail {
     pub to: Vec<String>,
     pub username: Option<String>,
     pub password: Option<String>,
+    /// Only deliver alerts at or above this severity through this notifier.
+    #[serde(default)]
+    pub min_severity: Severity,
 }
 
 fn default_smtp_tls() -> bool {
     true
 }
 
+/// Generic JSON webhook, e.g. a Slack/Discord/Mattermost incoming webhook URL.
+#[derive(Deserialize, Debug, Clone)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// When set, the outgoing payload is signed with
+    /// `X-Signature: sha256=<hmac>` using this shared secret, so receivers
+    /// can verify the notification actually came from this monitor.
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub min_severity: Severity,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct DesktopConfig {
+    #[serde(default)]
+    pub min_severity: Severity,
+}
+
+/// Embedded HTTP server that accepts Jenkins notification-plugin POSTs,
+/// letting build completions update `job_state` without waiting on a poll.
+#[derive(Deserialize, Debug, Clone)]
+pub struct WebhookServerConfig {
+    pub bind_addr: String,
+    pub secret: String,
+}
+
+/// Where to archive the console log of a build that triggers a quality-gate
+/// alert, so on-call engineers get immediate context without logging into
+/// Jenkins (and before the build rotates out of Jenkins' own retention).
+#[derive(Deserialize, Debug, Clone)]
+pub struct ConsoleArchiveConfig {
+    pub directory: String,
+    /// Gzip-compress archived logs. Defaults on since console logs compress well.
+    #[serde(default = "default_console_archive_gzip")]
+    pub gzip: bool,
+    /// Optional HTTP endpoint the archived log is also POSTed to, so a copy
+    /// survives even if this host's disk doesn't (e.g. an object storage
+    /// gateway).
+    pub remote_upload_url: Option<String>,
+}
+
+fn default_console_archive_gzip() -> bool {
+    true
+}
+
 pub struct ConfigReader;
 
 impl ConfigReader {
-    pub fn make() -> Config {
-        let conf = fs::read_to_string("config.toml").expect("cannot find config file");
-        toml::from_str(&conf).expect("syntax error in config file")
+    pub fn make(path: &str) -> Result<Config> {
+        let conf = fs::read_to_string(path)
+            .with_context(|| format!("cannot find config file '{}'", path))?;
+        toml::from_str(&conf).with_context(|| format!("syntax error in config file '{}'", path))
     }
 }
 
@@ -76,60 +294,112 @@ impl ConfigReader {
 mod tests {
     use super::*;
 
-    #[test]
-    fn config_email_default_smtp_tls_true_when_missing() {
-        let toml = r#"
-            [general]
-            log_level = "info"
+    const BASE_TOML: &str = r#"
+        [general]
+        log_level = "info"
 
-            [jenkins]
-            url = "https://jenkins.local"
-            username = "u"
-            password = "p"
+        [[jenkins]]
+        name = "primary"
+        url = "https://jenkins.local"
+        username = "u"
+        password = "p"
 
-            [[job]]
-            name = "j1"
-            schedule = "0 0 * * * *"
+        [[job]]
+        name = "j1"
+        schedule = "0 0 * * * *"
+    "#;
 
-            [email]
+    #[test]
+    fn config_email_default_smtp_tls_true_when_missing() {
+        let toml = format!(
+            r#"{}
+            [[notifier]]
+            kind = "email"
             smtp_host = "smtp.demo"
             smtp_port = 587
             from = "a@b"
             to = ["a@b"]
-        "#;
+        "#,
+            BASE_TOML
+        );
 
-        let c: Config = toml::from_str(toml).expect("should parse");
-        assert!(c.email.is_some());
-        let e = c.email.unwrap();
-        assert_eq!(e.smtp_tls, true, "smtp_tls defaults to true");
+        let c: Config = toml::from_str(&toml).expect("should parse");
+        assert_eq!(c.notifiers.len(), 1);
+        match &c.notifiers[0] {
+            NotifierConfig::Email(e) => assert_eq!(e.smtp_tls, true, "smtp_tls defaults to true"),
+            other => panic!("expected email notifier, got {:?}", other),
+        }
     }
 
     #[test]
     fn config_email_respects_smtp_tls_when_present() {
-        let toml = r#"
-            [general]
-            log_level = "info"
-
-            [jenkins]
-            url = "https://jenkins.local"
-            username = "u"
-            password = "p"
-
-            [[job]]
-            name = "j1"
-            schedule = "0 0 * * * *"
-
-            [email]
+        let toml = format!(
+            r#"{}
+            [[notifier]]
+            kind = "email"
             smtp_host = "smtp.demo"
             smtp_port = 587
             smtp_tls = false
             from = "a@b"
             to = ["a@b"]
-        "#;
+        "#,
+            BASE_TOML
+        );
+
+        let c: Config = toml::from_str(&toml).expect("should parse");
+        match &c.notifiers[0] {
+            NotifierConfig::Email(e) => assert_eq!(e.smtp_tls, false, "smtp_tls set to false"),
+            other => panic!("expected email notifier, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn config_accepts_multiple_notifier_kinds() {
+        let toml = format!(
+            r#"{}
+            [[notifier]]
+            kind = "webhook"
+            url = "https://hooks.example.com/services/x"
+
+            [[notifier]]
+            kind = "desktop"
+        "#,
+            BASE_TOML
+        );
+
+        let c: Config = toml::from_str(&toml).expect("should parse");
+        assert_eq!(c.notifiers.len(), 2);
+        assert!(matches!(c.notifiers[0], NotifierConfig::Webhook(_)));
+        assert!(matches!(c.notifiers[1], NotifierConfig::Desktop(_)));
+    }
+
+    #[test]
+    fn config_defaults_to_no_notifiers() {
+        let c: Config = toml::from_str(BASE_TOML).expect("should parse");
+        assert!(c.notifiers.is_empty());
+    }
+
+    #[test]
+    fn resolve_instance_falls_back_to_sole_instance() {
+        let c: Config = toml::from_str(BASE_TOML).expect("should parse");
+        assert_eq!(c.resolve_instance(None).unwrap().name, "primary");
+    }
+
+    #[test]
+    fn resolve_instance_requires_default_when_ambiguous() {
+        let toml = format!(
+            r#"{}
+            [[jenkins]]
+            name = "secondary"
+            url = "https://jenkins2.local"
+            username = "u"
+            password = "p"
+        "#,
+            BASE_TOML
+        );
 
-        let c: Config = toml::from_str(toml).expect("should parse");
-        assert!(c.email.is_some());
-        let e = c.email.unwrap();
-        assert_eq!(e.smtp_tls, false, "smtp_tls set to false");
+        let c: Config = toml::from_str(&toml).expect("should parse");
+        assert!(c.resolve_instance(None).is_err(), "ambiguous without a default should error");
+        assert_eq!(c.resolve_instance(Some("secondary")).unwrap().url, "https://jenkins2.local");
     }
 }
@@ -0,0 +1,86 @@
+use anyhow::{Context, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::ConsoleArchiveConfig;
+
+/// Write a build's console log to the configured archive directory, keyed
+/// by instance/job/build number, and optionally forward a copy to a remote
+/// upload target so diagnostics survive Jenkins' own build rotation. Returns
+/// the local path the log was written to.
+pub fn archive_console_log(
+    config: &ConsoleArchiveConfig,
+    instance: &str,
+    job: &str,
+    build_number: u64,
+    console_text: &str,
+) -> Result<PathBuf> {
+    fs::create_dir_all(&config.directory)
+        .with_context(|| format!("Failed to create console log archive directory '{}'", config.directory))?;
+
+    // Job names can contain folder separators (`/`); flatten them so the
+    // archive stays a single file per build rather than nested directories.
+    let safe_job = job.replace('/', "_");
+    let file_name = if config.gzip {
+        format!("{}_{}_{}.log.gz", instance, safe_job, build_number)
+    } else {
+        format!("{}_{}_{}.log", instance, safe_job, build_number)
+    };
+    let path = PathBuf::from(&config.directory).join(file_name);
+
+    if config.gzip {
+        let file = fs::File::create(&path)
+            .with_context(|| format!("Failed to create archive file '{}'", path.display()))?;
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        encoder
+            .write_all(console_text.as_bytes())
+            .with_context(|| format!("Failed to write gzip console log to '{}'", path.display()))?;
+        encoder
+            .finish()
+            .with_context(|| format!("Failed to finalize gzip console log at '{}'", path.display()))?;
+    } else {
+        fs::write(&path, console_text)
+            .with_context(|| format!("Failed to write console log to '{}'", path.display()))?;
+    }
+
+    log::info!(
+        "Archived console log for '{}/{}' build #{} to '{}'",
+        instance,
+        job,
+        build_number,
+        path.display()
+    );
+
+    if let Some(remote_url) = &config.remote_upload_url {
+        if let Err(e) = upload_remote(remote_url, &path) {
+            log::error!("Failed to upload archived console log to remote target: {}", e);
+        }
+    }
+
+    Ok(path)
+}
+
+fn upload_remote(remote_url: &str, path: &Path) -> Result<()> {
+    let bytes = fs::read(path)
+        .with_context(|| format!("Failed to read archived log '{}' for remote upload", path.display()))?;
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("console.log");
+
+    log::info!("Uploading archived console log '{}' to {}", file_name, remote_url);
+
+    let client = reqwest::blocking::Client::new();
+    let response = client
+        .post(remote_url)
+        .header("X-Archive-Filename", file_name)
+        .body(bytes)
+        .send()
+        .context("Failed to upload console log archive to remote target")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("Remote archive upload returned error status {}", response.status());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use rusqlite::{params, Connection};
+
+use crate::backend::BuildDetails;
+
+/// The persisted shape of a job's monitoring state, mirroring the in-memory
+/// `JobState` kept by `Monitor` so rows can be loaded straight back into it.
+pub struct JobStateRow {
+    pub last_check: DateTime<Utc>,
+    pub last_build_info: Option<BuildDetails>,
+    pub last_alert_sent: Option<DateTime<Utc>>,
+    pub alerting: bool,
+    pub alert_deferred_until: Option<DateTime<Utc>>,
+}
+
+/// SQLite-backed store for per-job monitoring state, so alert suppression
+/// and last-seen build info survive a process restart. Rows are keyed by
+/// `(instance, name)` since the same job name can exist on more than one
+/// configured Jenkins instance.
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn open(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)
+            .with_context(|| format!("Failed to open state database at '{}'", db_path))?;
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS job_state (
+                instance TEXT NOT NULL,
+                name TEXT NOT NULL,
+                last_check INTEGER NOT NULL,
+                last_build_number INTEGER,
+                last_build_timestamp INTEGER,
+                last_build_result TEXT,
+                last_build_duration_millis INTEGER,
+                last_alert_sent INTEGER,
+                alerting INTEGER NOT NULL DEFAULT 0,
+                alert_deferred_until INTEGER,
+                PRIMARY KEY (instance, name)
+             );
+             CREATE TABLE IF NOT EXISTS build_history (
+                instance TEXT NOT NULL,
+                name TEXT NOT NULL,
+                build_number INTEGER NOT NULL,
+                timestamp INTEGER NOT NULL,
+                result TEXT,
+                duration_millis INTEGER,
+                PRIMARY KEY (instance, name, build_number)
+             )",
+        )
+        .context("Failed to create job_state/build_history tables")?;
+
+        Ok(Self { conn })
+    }
+
+    /// Load every persisted job row, keyed by `(instance, name)`.
+    pub fn load_all(&self) -> Result<Vec<((String, String), JobStateRow)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT instance, name, last_check, last_build_number, last_build_timestamp, last_build_result, last_build_duration_millis, last_alert_sent, alerting, alert_deferred_until
+             FROM job_state",
+        )?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                let instance: String = row.get(0)?;
+                let name: String = row.get(1)?;
+                let last_check: i64 = row.get(2)?;
+                let last_build_number: Option<i64> = row.get(3)?;
+                let last_build_timestamp: Option<i64> = row.get(4)?;
+                let last_build_result: Option<String> = row.get(5)?;
+                let last_build_duration_millis: Option<i64> = row.get(6)?;
+                let last_alert_sent: Option<i64> = row.get(7)?;
+                let alerting: bool = row.get(8)?;
+                let alert_deferred_until: Option<i64> = row.get(9)?;
+                Ok((
+                    instance,
+                    name,
+                    last_check,
+                    last_build_number,
+                    last_build_timestamp,
+                    last_build_result,
+                    last_build_duration_millis,
+                    last_alert_sent,
+                    alerting,
+                    alert_deferred_until,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Failed to read job_state rows")?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(instance, name, last_check, number, timestamp, result, duration_millis, alert_sent, alerting, alert_deferred_until)| {
+                    let last_build_info = match (number, timestamp) {
+                        (Some(number), Some(timestamp)) => Some(BuildDetails {
+                            number: number as u64,
+                            timestamp: millis_to_utc(timestamp),
+                            result,
+                            duration_millis,
+                        }),
+                        _ => None,
+                    };
+
+                    (
+                        (instance, name),
+                        JobStateRow {
+                            last_check: millis_to_utc(last_check),
+                            last_build_info,
+                            last_alert_sent: alert_sent.map(millis_to_utc),
+                            alerting,
+                            alert_deferred_until: alert_deferred_until.map(millis_to_utc),
+                        },
+                    )
+                },
+            )
+            .collect())
+    }
+
+    /// Upsert the row for a single job on a single instance, called after
+    /// each `check_job` pass.
+    pub fn save(&self, instance: &str, name: &str, state: &JobStateRow) -> Result<()> {
+        self.conn
+            .execute(
+                "INSERT INTO job_state
+                    (instance, name, last_check, last_build_number, last_build_timestamp, last_build_result, last_build_duration_millis, last_alert_sent, alerting, alert_deferred_until)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                 ON CONFLICT(instance, name) DO UPDATE SET
+                    last_check = excluded.last_check,
+                    last_build_number = excluded.last_build_number,
+                    last_build_timestamp = excluded.last_build_timestamp,
+                    last_build_result = excluded.last_build_result,
+                    last_build_duration_millis = excluded.last_build_duration_millis,
+                    last_alert_sent = excluded.last_alert_sent,
+                    alerting = excluded.alerting,
+                    alert_deferred_until = excluded.alert_deferred_until",
+                params![
+                    instance,
+                    name,
+                    state.last_check.timestamp_millis(),
+                    state.last_build_info.as_ref().map(|b| b.number as i64),
+                    state
+                        .last_build_info
+                        .as_ref()
+                        .map(|b| b.timestamp.timestamp_millis()),
+                    state.last_build_info.as_ref().and_then(|b| b.result.clone()),
+                    state.last_build_info.as_ref().and_then(|b| b.duration_millis),
+                    state.last_alert_sent.map(|t| t.timestamp_millis()),
+                    state.alerting,
+                    state.alert_deferred_until.map(|t| t.timestamp_millis()),
+                ],
+            )
+            .with_context(|| format!("Failed to persist job_state row for '{}/{}'", instance, name))?;
+
+        Ok(())
+    }
+
+    /// Record a build in the audit-trail history table. A no-op if this
+    /// exact `(instance, name, build_number)` was already recorded.
+    pub fn record_build(&self, instance: &str, name: &str, build: &BuildDetails) -> Result<()> {
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO build_history (instance, name, build_number, timestamp, result, duration_millis)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    instance,
+                    name,
+                    build.number as i64,
+                    build.timestamp.timestamp_millis(),
+                    build.result,
+                    build.duration_millis,
+                ],
+            )
+            .with_context(|| format!("Failed to record build history for '{}/{}'", instance, name))?;
+
+        Ok(())
+    }
+}
+
+fn millis_to_utc(ms: i64) -> DateTime<Utc> {
+    Utc.timestamp_millis_opt(ms).single().unwrap_or_else(Utc::now)
+}
@@ -3,13 +3,13 @@ use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use std::time::Duration;
 
-use crate::config::JenkinsConfig;
+use crate::config::JenkinsInstanceConfig;
 
 pub struct JenkinsClient {
     client: reqwest::Client,
     base_url: String,
-    username: Option<String>,
-    api_token: Option<String>,
+    username: String,
+    password: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -23,6 +23,9 @@ struct BuildInfo {
     number: u64,
     timestamp: i64,
     result: Option<String>,
+    /// Build runtime in milliseconds, as reported by Jenkins. Absent while
+    /// the build is still running.
+    duration: Option<i64>,
 }
 
 #[derive(Debug, Clone)]
@@ -30,10 +33,11 @@ pub struct LastBuildInfo {
     pub number: u64,
     pub timestamp: DateTime<Utc>,
     pub result: Option<String>,
+    pub duration_millis: Option<i64>,
 }
 
 impl JenkinsClient {
-    pub fn new(config: &JenkinsConfig) -> Result<Self> {
+    pub fn new(config: &JenkinsInstanceConfig) -> Result<Self> {
         let client = reqwest::Client::builder()
             .timeout(Duration::from_secs(config.timeout_secs))
             .build()
@@ -43,22 +47,20 @@ impl JenkinsClient {
             client,
             base_url: config.url.trim_end_matches('/').to_string(),
             username: config.username.clone(),
-            api_token: config.api_token.clone(),
+            password: config.password.clone(),
         })
     }
-    
+
     pub async fn get_last_build(&self, job_name: &str) -> Result<Option<LastBuildInfo>> {
-        let url = format!("{}/job/{}/api/json", self.base_url, job_name);
-        
+        let url = build_job_api_url(&self.base_url, job_name);
+
         log::debug!("Fetching job info from: {}", url);
-        
-        let mut request = self.client.get(&url);
-        
-        // Add basic auth if credentials are provided
-        if let (Some(username), Some(token)) = (&self.username, &self.api_token) {
-            request = request.basic_auth(username, Some(token));
-        }
-        
+
+        let request = self
+            .client
+            .get(&url)
+            .basic_auth(&self.username, Some(&self.password));
+
         let response = request
             .send()
             .await
@@ -85,19 +87,85 @@ impl JenkinsClient {
                 number: build.number,
                 timestamp,
                 result: build.result,
+                duration_millis: build.duration,
             }
         }))
     }
     
+    /// Fetch the plain-text console log for a specific build via Jenkins'
+    /// `/consoleText` endpoint.
+    pub async fn get_console_text(&self, job_name: &str, build_number: u64) -> Result<String> {
+        let url = build_console_text_url(&self.base_url, job_name, build_number);
+
+        log::debug!("Fetching console log from: {}", url);
+
+        let response = self
+            .client
+            .get(&url)
+            .basic_auth(&self.username, Some(&self.password))
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch console log for '{}' build #{}", job_name, build_number))?;
+
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Jenkins API returned error status {} fetching console log for '{}' build #{}",
+                response.status(),
+                job_name,
+                build_number
+            );
+        }
+
+        response
+            .text()
+            .await
+            .with_context(|| format!("Failed to read console log body for '{}' build #{}", job_name, build_number))
+    }
+
+    /// The human-facing Jenkins page for a build's console output, for
+    /// inclusion in alert bodies so on-call engineers can jump straight in.
+    pub fn console_url(&self, job_name: &str, build_number: u64) -> String {
+        build_console_ui_url(&self.base_url, job_name, build_number)
+    }
+
+    /// Fetch a job's raw `config.xml`, so its `TimerTrigger` cron spec can
+    /// be read directly from Jenkins rather than relying on `config.toml`
+    /// staying in sync with it.
+    pub async fn get_config_xml(&self, job_name: &str) -> Result<String> {
+        let url = build_config_xml_url(&self.base_url, job_name);
+
+        log::debug!("Fetching job config from: {}", url);
+
+        let response = self
+            .client
+            .get(&url)
+            .basic_auth(&self.username, Some(&self.password))
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch config.xml for '{}'", job_name))?;
+
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Jenkins API returned error status {} fetching config.xml for '{}'",
+                response.status(),
+                job_name
+            );
+        }
+
+        response
+            .text()
+            .await
+            .with_context(|| format!("Failed to read config.xml body for '{}'", job_name))
+    }
+
     pub async fn test_connection(&self) -> Result<()> {
         let url = format!("{}/api/json", self.base_url);
-        
-        let mut request = self.client.get(&url);
-        
-        if let (Some(username), Some(token)) = (&self.username, &self.api_token) {
-            request = request.basic_auth(username, Some(token));
-        }
-        
+
+        let request = self
+            .client
+            .get(&url)
+            .basic_auth(&self.username, Some(&self.password));
+
         let response = request
             .send()
             .await
@@ -113,3 +181,156 @@ impl JenkinsClient {
         Ok(())
     }
 }
+
+/// Build a Jenkins job API URL that supports folder-qualified and
+/// multibranch pipeline job names. Jenkins expects nested jobs to use
+/// repeated `/job/{name}` segments, e.g. `folder/subfolder/jobname` becomes
+/// `/job/folder/job/subfolder/job/jobname/api/json`.
+fn build_job_api_url(base_url: &str, job_name: &str) -> String {
+    format!("{}/api/json", build_job_path(base_url, job_name))
+}
+
+/// Build the `/job/{name}` URL prefix shared by the job API, console text,
+/// and console page URLs.
+fn build_job_path(base_url: &str, job_name: &str) -> String {
+    let mut url = base_url.trim_end_matches('/').to_string();
+
+    for part in job_name.split('/') {
+        url.push_str(&format!("/job/{}", urlencoding::encode(part)));
+    }
+
+    url
+}
+
+fn build_console_text_url(base_url: &str, job_name: &str, build_number: u64) -> String {
+    format!("{}/{}/consoleText", build_job_path(base_url, job_name), build_number)
+}
+
+fn build_console_ui_url(base_url: &str, job_name: &str, build_number: u64) -> String {
+    format!("{}/{}/console", build_job_path(base_url, job_name), build_number)
+}
+
+fn build_config_xml_url(base_url: &str, job_name: &str) -> String {
+    format!("{}/config.xml", build_job_path(base_url, job_name))
+}
+
+/// Extract a job's `TimerTrigger` cron spec from its raw `config.xml`, if
+/// one is configured. Jenkins only ever emits a single
+/// `<hudson.triggers.TimerTrigger><spec>...</spec></hudson.triggers.TimerTrigger>`
+/// block, so a plain substring search is enough here without pulling in a
+/// full XML parser.
+pub fn extract_schedule_from_config_xml(xml: &str) -> Option<String> {
+    let trigger = &xml[xml.find("<hudson.triggers.TimerTrigger>")?..];
+    let spec_start = trigger.find("<spec>")? + "<spec>".len();
+    let spec_end = trigger[spec_start..].find("</spec>")?;
+    Some(normalize_cron_spec(&trigger[spec_start..spec_start + spec_end]))
+}
+
+/// Normalize a cron spec for the `cron` crate, which expects a seconds
+/// field. Jenkins `config.xml` (and many Jenkins UI specs) commonly use
+/// 5-field cron (minute hour day month weekday); prepend a `0` seconds
+/// field when that's what we find. Jenkins' `H` hash-based load-spreading
+/// placeholder isn't understood by the `cron` crate either, so plain `H`
+/// fields are normalized to a fixed `0` rather than left to fail parsing.
+/// Jenkins also pretty-prints `config.xml`, so the raw `<spec>` text is
+/// often indented across lines - collapse that whitespace first.
+pub(crate) fn normalize_cron_spec(spec: &str) -> String {
+    let fields: Vec<String> = spec.split_whitespace().map(normalize_cron_field).collect();
+
+    if fields.len() == 5 {
+        format!("0 {}", fields.join(" "))
+    } else {
+        fields.join(" ")
+    }
+}
+
+fn normalize_cron_field(field: &str) -> String {
+    if field == "H" {
+        "0".to_string()
+    } else if let Some(rest) = field.strip_prefix("H/") {
+        format!("0/{}", rest)
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_top_level_job_url() {
+        let got = build_job_api_url("https://jenkins.example.com/", "nightly-build");
+        assert_eq!(got, "https://jenkins.example.com/job/nightly-build/api/json");
+    }
+
+    #[test]
+    fn builds_nested_job_url_for_folders_and_multibranch_pipelines() {
+        let got = build_job_api_url("https://jenkins.example.com", "folder/subfolder/nightly build");
+        assert_eq!(
+            got,
+            "https://jenkins.example.com/job/folder/job/subfolder/job/nightly%20build/api/json"
+        );
+    }
+
+    #[test]
+    fn builds_console_text_url() {
+        let got = build_console_text_url("https://jenkins.example.com", "nightly-build", 42);
+        assert_eq!(got, "https://jenkins.example.com/job/nightly-build/42/consoleText");
+    }
+
+    #[test]
+    fn builds_console_ui_url() {
+        let got = build_console_ui_url("https://jenkins.example.com", "nightly-build", 42);
+        assert_eq!(got, "https://jenkins.example.com/job/nightly-build/42/console");
+    }
+
+    #[test]
+    fn builds_config_xml_url() {
+        let got = build_config_xml_url("https://jenkins.example.com", "nightly-build");
+        assert_eq!(got, "https://jenkins.example.com/job/nightly-build/config.xml");
+    }
+
+    #[test]
+    fn extracts_schedule_from_timer_trigger() {
+        let xml = r#"<?xml version='1.1' encoding='UTF-8'?>
+<project>
+  <triggers>
+    <hudson.triggers.TimerTrigger>
+      <spec>H 2 * * *</spec>
+    </hudson.triggers.TimerTrigger>
+  </triggers>
+</project>"#;
+        // The 5-field Jenkins spec is padded with a seconds field and its
+        // `H` hash placeholder normalized, so the result is parseable by
+        // the `cron` crate.
+        assert_eq!(extract_schedule_from_config_xml(xml), Some("0 0 2 * * *".to_string()));
+    }
+
+    #[test]
+    fn extracts_schedule_collapses_multiline_spec_whitespace() {
+        let xml = "<hudson.triggers.TimerTrigger>\n  <spec>\n    0 0 2\n    * * *\n  </spec>\n</hudson.triggers.TimerTrigger>";
+        assert_eq!(extract_schedule_from_config_xml(xml), Some("0 0 2 * * *".to_string()));
+    }
+
+    #[test]
+    fn extract_schedule_returns_none_without_timer_trigger() {
+        let xml = "<project><triggers/></project>";
+        assert_eq!(extract_schedule_from_config_xml(xml), None);
+    }
+
+    #[test]
+    fn normalize_cron_spec_pads_five_field_jenkins_spec() {
+        assert_eq!(normalize_cron_spec("H 2 * * *"), "0 0 2 * * *");
+    }
+
+    #[test]
+    fn normalize_cron_spec_leaves_six_field_spec_alone() {
+        assert_eq!(normalize_cron_spec("0 0 2 * * *"), "0 0 2 * * *");
+    }
+
+    #[test]
+    fn normalize_cron_spec_normalizes_hash_with_offset() {
+        assert_eq!(normalize_cron_spec("H/15 * * * *"), "0 0/15 * * * *");
+    }
+}
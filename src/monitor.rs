@@ -1,205 +1,744 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::{DateTime, Duration, Utc};
-use std::collections::HashMap;
+use chrono_tz::Tz;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::Arc;
+use tokio::sync::Mutex;
 
-use crate::alert::EmailAlerter;
-use crate::config::{Config, JobConfig};
-use crate::jenkins::{JenkinsClient, LastBuildInfo};
+use crate::backend::{BuildDetails, CIBackend};
+use crate::config::{Config, JenkinsInstanceConfig, JobConfig};
+use crate::console_archive;
+use crate::db::{JobStateRow, Store};
+use crate::notifier::{build_notifiers, Notifier, Severity};
+use crate::webhook;
 
 pub struct Monitor {
     config: Config,
-    jenkins_client: JenkinsClient,
-    email_alerter: Option<EmailAlerter>,
-    job_states: HashMap<String, JobState>,
+    /// One backend per configured `[[jenkins]]` instance, keyed by instance name.
+    backends: HashMap<String, Box<dyn CIBackend>>,
+    notifiers: Vec<Box<dyn Notifier>>,
+    shared: SharedJobStore,
 }
 
+/// Job state plus the SQLite handle it's persisted through, shared between
+/// the polling loop and the webhook listener so either path can update a
+/// job's last-seen build without stepping on the other.
+pub(crate) struct SharedState {
+    pub(crate) store: Store,
+    /// Keyed by `(instance name, job name)`, since the same job name can
+    /// exist on more than one configured Jenkins instance.
+    pub(crate) job_states: HashMap<(String, String), JobState>,
+}
+
+pub(crate) type SharedJobStore = Arc<Mutex<SharedState>>;
+
 #[derive(Debug, Clone)]
-struct JobState {
-    last_check: DateTime<Utc>,
-    last_build_info: Option<LastBuildInfo>,
-    last_alert_sent: Option<DateTime<Utc>>,
+pub(crate) struct JobState {
+    pub(crate) last_check: DateTime<Utc>,
+    pub(crate) last_build_info: Option<BuildDetails>,
+    pub(crate) last_alert_sent: Option<DateTime<Utc>>,
+    /// Set while a job has an unresolved alert outstanding, so we know to
+    /// send a "RESOLVED" notification the next time it produces a fresh build.
+    pub(crate) alerting: bool,
+    /// When a `working_hours` window deferred an otherwise-due alert, the
+    /// start of the next window, from which a check of this job may send it
+    /// instead of dropping it. Cleared once that alert goes out or the job
+    /// recovers.
+    pub(crate) alert_deferred_until: Option<DateTime<Utc>>,
 }
 
-impl Monitor {
-    pub fn new(config: Config, jenkins_client: JenkinsClient) -> Self {
-        let email_alerter = config.alerts.email.as_ref().map(|email_config| {
-            EmailAlerter::new(email_config.clone())
-        });
-        
-        let job_states = HashMap::new();
-        
+impl JobState {
+    pub(crate) fn new_at(now: DateTime<Utc>) -> Self {
         Self {
+            last_check: now,
+            last_build_info: None,
+            last_alert_sent: None,
+            alerting: false,
+            alert_deferred_until: None,
+        }
+    }
+}
+
+impl Monitor {
+    pub fn new(config: Config, backends: HashMap<String, Box<dyn CIBackend>>) -> Result<Self> {
+        let notifiers = build_notifiers(&config.notifiers);
+
+        let store = Store::open(&config.general.db_path)?;
+        let job_states = store
+            .load_all()?
+            .into_iter()
+            .map(|(key, row)| {
+                (
+                    key,
+                    JobState {
+                        last_check: row.last_check,
+                        last_build_info: row.last_build_info,
+                        last_alert_sent: row.last_alert_sent,
+                        alerting: row.alerting,
+                        alert_deferred_until: row.alert_deferred_until,
+                    },
+                )
+            })
+            .collect();
+
+        let shared = Arc::new(Mutex::new(SharedState { store, job_states }));
+
+        Ok(Self {
             config,
-            jenkins_client,
-            email_alerter,
-            job_states,
-        }
-    }
-    
-    pub async fn run(mut self) -> Result<()> {
-        // Test Jenkins connection first
-        log::info!("Testing Jenkins connection...");
-        self.jenkins_client.test_connection().await?;
-        log::info!("Jenkins connection successful");
-        
-        let check_interval = tokio::time::Duration::from_secs(60);
-        let mut interval = tokio::time::interval(check_interval);
-        
+            backends,
+            notifiers,
+            shared,
+        })
+    }
+
+    pub async fn run(self: Arc<Self>) -> Result<()> {
+        // Test connectivity to every configured instance first
+        for (name, backend) in &self.backends {
+            log::info!("Testing connection to Jenkins instance '{}'...", name);
+            backend.test_connection().await?;
+            log::info!("Jenkins instance '{}' connection successful", name);
+        }
+
+        if let Some(webhook_config) = self.config.webhook.clone() {
+            let monitor = Arc::clone(&self);
+            tokio::spawn(async move {
+                if let Err(e) = webhook::serve(webhook_config, monitor).await {
+                    log::error!("Webhook listener exited with error: {}", e);
+                }
+            });
+        }
+
+        // Rather than polling every job on a single fixed interval, keep a
+        // min-heap of per-job deadlines (next cron occurrence plus
+        // `alert_threshold_minutes`) and sleep until the earliest one. This
+        // lets a once-a-day job be checked seconds after its window closes
+        // instead of up to a full polling interval later, without hammering
+        // the Jenkins API for jobs that aren't due yet.
+        let min_poll_interval =
+            tokio::time::Duration::from_secs(self.config.general.min_poll_interval_seconds.max(1));
+
+        let startup_now = Utc::now();
+        let mut heap: BinaryHeap<ScheduledCheck> = BinaryHeap::new();
+        for job_config in &self.config.job {
+            if !job_config.enabled {
+                continue;
+            }
+            match self.initial_deadline(job_config, startup_now).await {
+                Ok(due_at) => heap.push(ScheduledCheck {
+                    due_at,
+                    job: job_config.clone(),
+                }),
+                Err(e) => log::error!("Failed to schedule job '{}': {}", job_config.name, e),
+            }
+        }
+
         loop {
-            interval.tick().await;
-            
-            log::info!("Running monitoring check...");
-            
-            if let Err(e) = self.check_all_jobs().await {
-                log::error!("Error during monitoring check: {}", e);
+            let now = Utc::now();
+
+            // Clamped to `min_poll_interval_seconds` so a tight cluster of
+            // deadlines can't turn into a busy loop waking up every few
+            // milliseconds; this can delay an already-due job by up to the
+            // floor, which is the point.
+            let sleep_for = heap
+                .peek()
+                .map(|next| next.due_at.signed_duration_since(now))
+                .and_then(|remaining| remaining.to_std().ok())
+                .unwrap_or(tokio::time::Duration::ZERO)
+                .max(min_poll_interval);
+
+            tokio::time::sleep(sleep_for).await;
+
+            let now = Utc::now();
+            let mut due_jobs = Vec::new();
+            while matches!(heap.peek(), Some(next) if next.due_at <= now) {
+                if let Some(check) = heap.pop() {
+                    due_jobs.push(check.job);
+                }
+            }
+
+            if due_jobs.is_empty() {
+                // The heap was empty (no enabled jobs) or the floor woke us
+                // before anything was actually due; loop back around.
+                continue;
+            }
+
+            log::info!("Running monitoring check for {} due job(s)...", due_jobs.len());
+
+            // A job's next deadline only depends on its own schedule, not on
+            // the outcome of the check that's about to run, so reschedule
+            // everything up front rather than waiting on the (possibly slow,
+            // possibly concurrent) checks below.
+            for job_config in &due_jobs {
+                match self.next_deadline(job_config).await {
+                    Ok(due_at) => heap.push(ScheduledCheck {
+                        due_at,
+                        job: job_config.clone(),
+                    }),
+                    Err(e) => log::error!("Failed to reschedule job '{}': {}", job_config.name, e),
+                }
+            }
+
+            Arc::clone(&self).check_jobs_concurrently(due_jobs, now, false).await;
+        }
+    }
+
+    /// The deadline to seed the scheduler with at startup: the most recent
+    /// cron occurrence (which may already be in the past) plus the job's
+    /// `alert_threshold_minutes`. Unlike `next_deadline`, this can resolve
+    /// to a time at or before `now`, so a run that was already missed
+    /// before the daemon started is evaluated on the very first loop
+    /// iteration instead of waiting for the next occurrence to come due.
+    async fn initial_deadline(&self, job_config: &JobConfig, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        let tz = self.config.resolve_timezone(job_config)?;
+        let schedule = self.resolve_job_schedule(job_config).await?;
+        let expected_run_time = calculate_expected_run_time(&schedule, now, tz)?;
+        Ok(expected_run_time + Duration::minutes(job_config.alert_threshold_minutes))
+    }
+
+    /// The next instant `job_config`'s check becomes due: its next cron
+    /// occurrence, plus its configured `alert_threshold_minutes`. Computed
+    /// fresh each time so a job can be rescheduled after every check.
+    async fn next_deadline(&self, job_config: &JobConfig) -> Result<DateTime<Utc>> {
+        let tz = self.config.resolve_timezone(job_config)?;
+        let schedule = self.resolve_job_schedule(job_config).await?;
+        let next_occurrence = next_cron_occurrence(&schedule, tz)?;
+        Ok(next_occurrence + Duration::minutes(job_config.alert_threshold_minutes))
+    }
+
+    /// Resolve the cron spec to evaluate a job's schedule against: the
+    /// schedule Jenkins itself reports for the job (its `config.xml`,
+    /// fetched through the backend and cached behind `CachingBackend` so
+    /// this doesn't hit the network on every scheduling evaluation),
+    /// falling back to the configured `schedule` when the backend has no
+    /// opinion or the lookup fails.
+    async fn resolve_job_schedule(&self, job_config: &JobConfig) -> Result<String> {
+        let instance = self.config.resolve_instance(job_config.instance.as_deref())?;
+        let backend = self
+            .backends
+            .get(&instance.name)
+            .with_context(|| format!("no backend initialized for Jenkins instance '{}'", instance.name))?;
+
+        match backend.job_schedule(&job_config.name).await {
+            Ok(Some(spec)) => Ok(spec),
+            Ok(None) => Ok(job_config.schedule.clone()),
+            Err(e) => {
+                log::warn!(
+                    "Job '{}': failed to fetch schedule from Jenkins, falling back to configured schedule: {}",
+                    job_config.name,
+                    e
+                );
+                Ok(job_config.schedule.clone())
             }
         }
     }
-    
-    async fn check_all_jobs(&mut self) -> Result<()> {
+
+    /// Run a single monitoring pass over every enabled job and return. Used
+    /// by the `check-once` CLI command.
+    pub async fn check_once(self: Arc<Self>) -> Result<()> {
         let now = Utc::now();
-        
+
         // Clone the job configs to avoid borrow issues
-        let jobs: Vec<JobConfig> = self.config.jobs.clone();
-        
+        let jobs: Vec<JobConfig> = self.config.job.clone();
+
+        let mut due = Vec::with_capacity(jobs.len());
         for job_config in jobs {
             if !job_config.enabled {
                 log::debug!("Skipping disabled job: {}", job_config.name);
+                println!("{}: skipped (disabled)", job_config.name);
                 continue;
             }
-            
-            if let Err(e) = self.check_job(&job_config, now).await {
-                log::error!("Error checking job '{}': {}", job_config.name, e);
-            }
+            due.push(job_config);
         }
-        
+
+        self.check_jobs_concurrently(due, now, true).await;
+
         Ok(())
     }
-    
-    async fn check_job(&mut self, job_config: &JobConfig, now: DateTime<Utc>) -> Result<()> {
-        log::info!("Checking job: {}", job_config.name);
-        
-        // Get current build info from Jenkins
-        let current_build = self.jenkins_client.get_last_build(&job_config.name).await?;
-        
-        // Check if job should have run
-        let expected_run_time = self.calculate_expected_run_time(&job_config.expected_schedule, now)?;
-        let threshold = Duration::minutes(job_config.alert_threshold_mins as i64);
-        
-        // Get or create job state
-        let state = self.job_states.entry(job_config.name.clone()).or_insert_with(|| {
-            JobState {
-                last_check: now,
-                last_build_info: None,
-                last_alert_sent: None,
-            }
-        });
-        
-        // If we have a last build, check if it's recent enough
-        let should_alert = if let Some(ref build_info) = current_build {
-            let time_since_expected = now.signed_duration_since(expected_run_time);
-            
-            log::debug!(
-                "Job '{}': last build at {}, expected at {}, threshold {} mins",
-                job_config.name,
-                build_info.timestamp,
+
+    /// Dispatch `check_job` across up to `max_concurrent_checks` jobs at
+    /// once, bounded by a semaphore so a cycle completes in roughly the
+    /// slowest single check instead of the sum of all of them, without
+    /// opening more simultaneous Jenkins requests than configured. Results
+    /// are awaited back and notifications dispatched in dispatch order (not
+    /// completion order), so alert delivery stays deterministic across a
+    /// cycle even though the checks themselves ran concurrently. Per-job
+    /// `println!` output is gated on `announce`, since continuous `run`
+    /// mode shouldn't spam stdout every cycle the way `check-once` does.
+    async fn check_jobs_concurrently(self: Arc<Self>, jobs: Vec<JobConfig>, now: DateTime<Utc>, announce: bool) {
+        let max_concurrent = self.config.general.max_concurrent_checks.max(1);
+        let semaphore = Arc::new(tokio::sync::Semaphore::new(max_concurrent));
+
+        let handles: Vec<_> = jobs
+            .into_iter()
+            .map(|job_config| {
+                let monitor = Arc::clone(&self);
+                let semaphore = Arc::clone(&semaphore);
+                tokio::spawn(async move {
+                    let _permit = semaphore
+                        .acquire_owned()
+                        .await
+                        .expect("check semaphore is never closed");
+                    let result = monitor.check_job(&job_config, now).await;
+                    (job_config, result)
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            match handle.await {
+                Ok((job_config, Ok(pending))) => {
+                    if announce {
+                        println!("{}: checked", job_config.name);
+                    }
+                    if let Err(e) = self.dispatch_notification(pending, now).await {
+                        log::error!("Failed to deliver notification for job '{}': {}", job_config.name, e);
+                    }
+                }
+                Ok((job_config, Err(e))) => {
+                    log::error!("Error checking job '{}': {}", job_config.name, e);
+                    if announce {
+                        println!("{}: error ({})", job_config.name, e);
+                    }
+
+                    let should_alert =
+                        job_config.alert_on_error.unwrap_or(self.config.general.alert_on_check_error);
+                    if should_alert {
+                        if let Err(send_err) = self.send_check_error_alert(&job_config, &e).await {
+                            log::error!(
+                                "Failed to send check-error alert for job '{}': {}",
+                                job_config.name,
+                                send_err
+                            );
+                        }
+                    } else {
+                        log::debug!(
+                            "Alert-on-error disabled for job '{}' (global: {}) - not sending alert",
+                            job_config.name,
+                            self.config.general.alert_on_check_error
+                        );
+                    }
+                }
+                Err(e) => log::error!("Job check task panicked: {}", e),
+            }
+        }
+    }
+
+    /// Send whatever notification `check_job` decided was due, resolving
+    /// the job's instance config fresh rather than carrying a borrow of it
+    /// across the `tokio::spawn` boundary in `check_jobs_concurrently`.
+    async fn dispatch_notification(&self, pending: PendingNotification, now: DateTime<Utc>) -> Result<()> {
+        match pending {
+            PendingNotification::None => Ok(()),
+            PendingNotification::Overdue {
+                job_config,
+                instance_name,
+                current_build,
                 expected_run_time,
-                job_config.alert_threshold_mins
-            );
-            
-            // Alert if the job hasn't run since the expected time + threshold
-            if time_since_expected > threshold && build_info.timestamp < expected_run_time {
+            } => {
+                let instance = self.config.resolve_instance(Some(&instance_name))?;
+                self.send_alert(&job_config, instance, &current_build, expected_run_time, now).await
+            }
+            PendingNotification::QualityGate {
+                job_config,
+                instance_name,
+                current_build,
+                quality_issues,
+            } => {
+                let instance = self.config.resolve_instance(Some(&instance_name))?;
+                self.send_quality_gate_alert(&job_config, instance, &current_build, &quality_issues, now).await
+            }
+            PendingNotification::Resolved {
+                job_config,
+                instance_name,
+                current_build,
+            } => {
+                let instance = self.config.resolve_instance(Some(&instance_name))?;
+                self.send_resolved_notification(&job_config, instance, &current_build, now).await
+            }
+        }
+    }
+
+    async fn check_job(&self, job_config: &JobConfig, now: DateTime<Utc>) -> Result<PendingNotification> {
+        let instance = self.config.resolve_instance(job_config.instance.as_deref())?;
+        log::info!("Checking job: {} (instance: {})", job_config.name, instance.name);
+
+        let backend = self
+            .backends
+            .get(&instance.name)
+            .with_context(|| format!("no backend initialized for Jenkins instance '{}'", instance.name))?;
+
+        // Get current build info from the CI backend
+        let current_build = match backend.last_build_handle(&job_config.name).await? {
+            Some(handle) => Some(backend.build_details(&handle).await?),
+            None => None,
+        };
+
+        // Check if job should have run. Prefer the schedule Jenkins itself
+        // reports (cached behind `CachingBackend`) over the configured one,
+        // so a cron edit made directly in Jenkins' job config is honored
+        // without needing a matching `config.toml` change.
+        let tz = self.config.resolve_timezone(job_config)?;
+        let configured_schedule = match backend.job_schedule(&job_config.name).await {
+            Ok(Some(spec)) => spec,
+            Ok(None) => job_config.schedule.clone(),
+            Err(e) => {
                 log::warn!(
-                    "Job '{}' hasn't run since expected time. Last build: {}, Expected: {}",
+                    "Job '{}': failed to fetch schedule from Jenkins, falling back to configured schedule: {}",
+                    job_config.name,
+                    e
+                );
+                job_config.schedule.clone()
+            }
+        };
+        let expected_run_time = calculate_expected_run_time(&configured_schedule, now, tz)?;
+        let threshold = Duration::minutes(job_config.alert_threshold_minutes);
+
+        let (should_alert, should_send_alert, resolved, overdue_alert, quality_issues) = {
+            let mut shared = self.shared.lock().await;
+            let state = shared
+                .job_states
+                .entry((instance.name.clone(), job_config.name.clone()))
+                .or_insert_with(|| JobState::new_at(now));
+
+            let previous_build_number = state.last_build_info.as_ref().map(|b| b.number);
+            let was_alerting = state.alerting;
+
+            // If we have a last build, check if it's recent enough
+            let overdue_alert = if let Some(ref build_info) = current_build {
+                let time_since_expected = now.signed_duration_since(expected_run_time);
+
+                log::debug!(
+                    "Job '{}': last build at {}, expected at {}, threshold {} mins",
                     job_config.name,
                     build_info.timestamp,
-                    expected_run_time
+                    expected_run_time,
+                    job_config.alert_threshold_minutes
                 );
-                true
+
+                // Alert if the job hasn't run since the expected time + threshold
+                if time_since_expected > threshold && build_info.timestamp < expected_run_time {
+                    log::warn!(
+                        "Job '{}' hasn't run since expected time. Last build: {}, Expected: {}",
+                        job_config.name,
+                        build_info.timestamp,
+                        expected_run_time
+                    );
+                    true
+                } else {
+                    false
+                }
             } else {
-                false
+                // No builds found - alert if we're past the expected time + threshold
+                let time_since_expected = now.signed_duration_since(expected_run_time);
+                if time_since_expected > threshold {
+                    log::warn!("Job '{}' has no builds and is past expected run time", job_config.name);
+                    true
+                } else {
+                    false
+                }
+            };
+
+            // Collect every reason the current build fails a quality check,
+            // independent of whether the job ran on schedule. All three are
+            // evaluated even when more than one applies, so the alert body
+            // can report the full picture.
+            let mut quality_issues: Vec<String> = Vec::new();
+
+            // A fresh build whose result is in `alert_on_result` is a quality
+            // gate failure - alert even though the job ran right on schedule.
+            if let Some(build) = current_build.as_ref() {
+                if let Some(result) = build.result.as_ref().filter(|result| {
+                    job_config
+                        .alert_on_result
+                        .iter()
+                        .any(|configured| configured.eq_ignore_ascii_case(result))
+                }) {
+                    log::warn!(
+                        "Job '{}' build #{} finished with result '{}', which is configured to alert",
+                        job_config.name,
+                        build.number,
+                        result
+                    );
+                    quality_issues.push(format!(
+                        "Build #{} finished with result '{}', which is configured to alert",
+                        build.number, result
+                    ));
+                }
             }
-        } else {
-            // No builds found - alert if we're past the expected time + threshold
-            let time_since_expected = now.signed_duration_since(expected_run_time);
-            if time_since_expected > threshold {
-                log::warn!("Job '{}' has no builds and is past expected run time", job_config.name);
-                true
+
+            // A build that must complete within a daily maintenance window
+            // but didn't is a problem even if it otherwise ran on schedule.
+            if let Some(window) = job_config.daily_window.as_deref() {
+                if let Some(build) = current_build.as_ref() {
+                    match build_within_daily_window(build.timestamp, tz, window) {
+                        Ok(true) => {}
+                        Ok(false) => {
+                            let local_time = build.timestamp.with_timezone(&tz).format("%H:%M");
+                            log::warn!(
+                                "Job '{}' build #{} finished at {} local time, outside the configured daily window {}",
+                                job_config.name,
+                                build.number,
+                                local_time,
+                                window
+                            );
+                            quality_issues.push(format!(
+                                "Build #{} finished at {} local time, outside the configured daily window {}",
+                                build.number, local_time, window
+                            ));
+                        }
+                        Err(e) => log::warn!(
+                            "Job '{}': invalid daily_window '{}': {}",
+                            job_config.name,
+                            window,
+                            e
+                        ),
+                    }
+                }
+            }
+
+            // A build that ran far longer than usual may have succeeded while
+            // still being hung or degraded.
+            if let Some(ceiling) = job_config.max_build_duration_minutes {
+                if let Some(minutes) = current_build.as_ref().and_then(|b| b.duration_minutes()) {
+                    if minutes > ceiling {
+                        let build_number = current_build.as_ref().map(|b| b.number).unwrap_or_default();
+                        log::warn!(
+                            "Job '{}' build #{} ran for {} minutes, exceeding the configured {} minute ceiling",
+                            job_config.name,
+                            build_number,
+                            minutes,
+                            ceiling
+                        );
+                        quality_issues.push(format!(
+                            "Build #{} ran for {} minutes, exceeding the configured {} minute ceiling",
+                            build_number, minutes, ceiling
+                        ));
+                    }
+                }
+            }
+
+            let should_alert = overdue_alert || !quality_issues.is_empty();
+
+            // Suppress repeat alerts until the configured reminder interval
+            // elapses, unless a previously deferred alert's working-hours
+            // window has now opened and is due regardless.
+            let reminder_interval = Duration::minutes(self.config.resolve_reminder_interval_minutes(job_config));
+            let reminder_due = if should_alert {
+                state
+                    .last_alert_sent
+                    .map(|last| now.signed_duration_since(last) > reminder_interval)
+                    .unwrap_or(true)
             } else {
                 false
+            };
+            // Only a currently-active issue can have a deferred alert come
+            // due; otherwise a job that recovered before its deferred
+            // alert's working-hours window opened would still fire a stale
+            // alert once that window arrived.
+            let deferred_due =
+                should_alert && state.alert_deferred_until.map(|due| now >= due).unwrap_or(false);
+            let alert_due = reminder_due || deferred_due;
+
+            // An alert that's due may still need to wait for the configured
+            // working-hours window to open, so it doesn't wake anyone up
+            // outside it. Rather than dropping it, remember when the next
+            // window starts; the first check of this job that runs inside an
+            // open window sends it. No extra check is scheduled for that
+            // instant, so the alert waits for the job's own next deadline.
+            let (should_send_alert, defer_until) = if alert_due {
+                match self.config.resolve_working_hours(job_config) {
+                    Some(window) => match is_within_working_hours(now, tz, window) {
+                        Ok(true) => (true, None),
+                        Ok(false) => match next_working_window_start(now, tz, window) {
+                            Ok(next_start) => (false, Some(next_start)),
+                            Err(e) => {
+                                log::warn!(
+                                    "Job '{}': failed to compute next working_hours window, alerting immediately: {}",
+                                    job_config.name,
+                                    e
+                                );
+                                (true, None)
+                            }
+                        },
+                        Err(e) => {
+                            log::warn!(
+                                "Job '{}': invalid working_hours '{}', alerting immediately: {}",
+                                job_config.name,
+                                window,
+                                e
+                            );
+                            (true, None)
+                        }
+                    },
+                    None => (true, None),
+                }
+            } else {
+                (false, None)
+            };
+
+            // A job that was alerting and has just produced a fresh build is
+            // considered recovered, even if that build itself is still within
+            // the current evaluation window.
+            let job_ran_again = current_build
+                .as_ref()
+                .map(|b| Some(b.number) != previous_build_number)
+                .unwrap_or(false);
+            let resolved = was_alerting && !should_alert && job_ran_again;
+
+            // Update state
+            state.last_check = now;
+            state.last_build_info = current_build.clone();
+            if should_send_alert {
+                state.last_alert_sent = Some(now);
+                state.alerting = true;
+                state.alert_deferred_until = None;
+            } else if let Some(defer_until) = defer_until {
+                log::info!(
+                    "Job '{}': alert due but outside working_hours, deferring to {}",
+                    job_config.name,
+                    defer_until
+                );
+                state.alert_deferred_until = Some(defer_until);
+            } else if !should_alert {
+                // The job is no longer in a bad state, so any stale
+                // deferred-alert instant from a previous cycle no longer
+                // applies - otherwise it would go off once its window opens
+                // even though there's nothing left to alert about.
+                state.alert_deferred_until = None;
             }
+            if resolved {
+                state.alerting = false;
+                state.alert_deferred_until = None;
+            }
+
+            // Persist the touched row so alert suppression survives a restart.
+            let row = JobStateRow {
+                last_check: state.last_check,
+                last_build_info: state.last_build_info.clone(),
+                last_alert_sent: state.last_alert_sent,
+                alerting: state.alerting,
+                alert_deferred_until: state.alert_deferred_until,
+            };
+            shared.store.save(&instance.name, &job_config.name, &row)?;
+
+            if let Some(build) = current_build.as_ref() {
+                shared.store.record_build(&instance.name, &job_config.name, build)?;
+            }
+
+            (should_alert, should_send_alert, resolved, overdue_alert, quality_issues)
         };
-        
-        // Check if we should send an alert (not sent recently)
-        let should_send_alert = if should_alert {
-            state.last_alert_sent
-                .map(|last| now.signed_duration_since(last) > Duration::hours(1))
-                .unwrap_or(true)
+
+        // Decide what notification is needed, leaving delivery to the
+        // caller, which sends it as it joins each concurrently-checked job
+        // in dispatch order. An overdue job takes
+        // priority in the message even if its last build also happened to
+        // fail a quality check.
+        let pending = if should_send_alert && overdue_alert {
+            PendingNotification::Overdue {
+                job_config: job_config.clone(),
+                instance_name: instance.name.clone(),
+                current_build,
+                expected_run_time,
+            }
+        } else if should_send_alert {
+            PendingNotification::QualityGate {
+                job_config: job_config.clone(),
+                instance_name: instance.name.clone(),
+                current_build,
+                quality_issues,
+            }
+        } else if resolved {
+            PendingNotification::Resolved {
+                job_config: job_config.clone(),
+                instance_name: instance.name.clone(),
+                current_build,
+            }
         } else {
-            false
-        };
-        
-        // Update state
-        state.last_check = now;
-        state.last_build_info = current_build.clone();
-        
-        // Send alert if needed
-        if should_send_alert {
-            self.send_alert(job_config, &current_build, expected_run_time, now).await?;
-            // Update last alert time
-            if let Some(state) = self.job_states.get_mut(&job_config.name) {
-                state.last_alert_sent = Some(now);
+            if should_alert {
+                log::debug!("Alert suppressed for job '{}' - already sent recently", job_config.name);
             }
-        } else if should_alert {
-            log::debug!("Alert suppressed for job '{}' - already sent recently", job_config.name);
-        }
-        
-        Ok(())
+            PendingNotification::None
+        };
+
+        Ok(pending)
     }
-    
-    fn calculate_expected_run_time(&self, cron_expr: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
-        use cron::Schedule;
-        use std::str::FromStr;
-        
-        let schedule = Schedule::from_str(cron_expr)?;
-        
-        // Find the most recent expected run time before now
-        let mut expected_time = now;
-        for upcoming in schedule.upcoming(Utc).take(10) {
-            if upcoming > now {
-                break;
-            }
-            expected_time = upcoming;
+
+    /// Fetch and archive a build's console log when `[console_archive]` is
+    /// configured, returning the local archive path and the Jenkins console
+    /// URL for inclusion in an alert body. Returns `None` when no archive
+    /// directory is configured.
+    async fn archive_console_log(
+        &self,
+        job_config: &JobConfig,
+        instance: &JenkinsInstanceConfig,
+        build_number: u64,
+    ) -> Result<Option<(std::path::PathBuf, String)>> {
+        let archive_config = match &self.config.console_archive {
+            Some(c) => c,
+            None => return Ok(None),
+        };
+
+        let backend = self
+            .backends
+            .get(&instance.name)
+            .with_context(|| format!("no backend initialized for Jenkins instance '{}'", instance.name))?;
+
+        let console_text = backend.console_log(&job_config.name, build_number).await?;
+        let path = console_archive::archive_console_log(
+            archive_config,
+            &instance.name,
+            &job_config.name,
+            build_number,
+            &console_text,
+        )?;
+        let console_url = backend.console_url(&job_config.name, build_number);
+
+        Ok(Some((path, console_url)))
+    }
+
+    /// Notify that checking a job failed outright (e.g. a Jenkins API
+    /// timeout), as opposed to the job having failed to run on schedule.
+    /// Gated per-job by `alert_on_error`, falling back to
+    /// `[general].alert_on_check_error`, so a flaky backend doesn't have to
+    /// page anyone if the operator doesn't want it to.
+    async fn send_check_error_alert(&self, job_config: &JobConfig, error: &anyhow::Error) -> Result<()> {
+        let subject = format!("Jenkins Monitor: check failed for '{}'", job_config.name);
+        let body = format!(
+            "Failed to check job '{}'. Error details:\n\n{:#}\n\nCheck the monitor logs for the full error chain.",
+            job_config.name, error
+        );
+
+        if self.notifiers.is_empty() {
+            log::warn!("No notifiers configured - alert would have been sent:");
+            log::warn!("Subject: {}", subject);
+            log::warn!("Body:\n{}", body);
         }
-        
-        // If we couldn't find a recent time, find the last time before now
-        if expected_time == now {
-            // Go back in time to find the last expected run
-            let past_time = now - Duration::days(7);
-            for upcoming in schedule.after(&past_time).take(1000) {
-                if upcoming > now {
-                    break;
-                }
-                expected_time = upcoming;
+
+        for notifier in &self.notifiers {
+            if let Err(e) = notifier.notify(Severity::Critical, &subject, &body) {
+                log::error!("Failed to deliver check-error alert for job '{}': {}", job_config.name, e);
             }
         }
-        
-        Ok(expected_time)
+
+        Ok(())
     }
-    
+
     async fn send_alert(
         &self,
         job_config: &JobConfig,
-        last_build: &Option<LastBuildInfo>,
+        instance: &JenkinsInstanceConfig,
+        last_build: &Option<BuildDetails>,
         expected_time: DateTime<Utc>,
         now: DateTime<Utc>,
     ) -> Result<()> {
-        let subject = format!("Jenkins Job Alert: {}", job_config.name);
-        
+        let subject = format!("Jenkins Job Alert: {} (instance: {})", job_config.name, instance.name);
+
         let body = if let Some(build) = last_build {
             format!(
                 "Jenkins Monitor Alert\n\n\
                 Job: {}\n\
+                Instance: {}\n\
                 Status: Job has not run as expected\n\n\
                 Expected Schedule: {}\n\
                 Last Expected Run: {}\n\
@@ -211,20 +750,22 @@ impl Monitor {
                 Please check Jenkins for issues.\n\n\
                 Jenkins URL: {}/job/{}",
                 job_config.name,
-                job_config.expected_schedule,
+                instance.name,
+                job_config.schedule,
                 expected_time.format("%Y-%m-%d %H:%M:%S UTC"),
                 build.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
                 build.number,
                 build.result.as_deref().unwrap_or("UNKNOWN"),
                 now.signed_duration_since(build.timestamp).num_minutes(),
-                job_config.alert_threshold_mins,
-                self.config.jenkins.url,
+                job_config.alert_threshold_minutes,
+                instance.url,
                 job_config.name
             )
         } else {
             format!(
                 "Jenkins Monitor Alert\n\n\
                 Job: {}\n\
+                Instance: {}\n\
                 Status: No builds found\n\n\
                 Expected Schedule: {}\n\
                 Last Expected Run: {}\n\
@@ -234,22 +775,502 @@ impl Monitor {
                 Please check Jenkins for issues.\n\n\
                 Jenkins URL: {}/job/{}",
                 job_config.name,
-                job_config.expected_schedule,
+                instance.name,
+                job_config.schedule,
                 expected_time.format("%Y-%m-%d %H:%M:%S UTC"),
-                job_config.alert_threshold_mins,
-                self.config.jenkins.url,
+                job_config.alert_threshold_minutes,
+                instance.url,
                 job_config.name
             )
         };
-        
-        if let Some(alerter) = &self.email_alerter {
-            alerter.send_alert(&subject, &body)?;
-        } else {
-            log::warn!("No email alerter configured - alert would have been sent:");
+
+        if self.notifiers.is_empty() {
+            log::warn!("No notifiers configured - alert would have been sent:");
             log::warn!("Subject: {}", subject);
             log::warn!("Body:\n{}", body);
         }
-        
+
+        for notifier in &self.notifiers {
+            if let Err(e) = notifier.notify(Severity::Critical, &subject, &body) {
+                log::error!("Failed to deliver alert for job '{}': {}", job_config.name, e);
+            }
+        }
+
         Ok(())
     }
+
+    /// Notify that a job ran on schedule but its most recent build failed
+    /// one or more quality checks: a configured `alert_on_result` value, a
+    /// `daily_window` miss, or a `max_build_duration_minutes` overrun.
+    async fn send_quality_gate_alert(
+        &self,
+        job_config: &JobConfig,
+        instance: &JenkinsInstanceConfig,
+        last_build: &Option<BuildDetails>,
+        issues: &[String],
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let build = match last_build {
+            Some(build) => build,
+            None => return Ok(()),
+        };
+
+        let console_section = match self.archive_console_log(job_config, instance, build.number).await {
+            Ok(Some((path, console_url))) => format!(
+                "Archived Console Log: {}\nJenkins Console: {}\n\n",
+                path.display(),
+                console_url
+            ),
+            Ok(None) => String::new(),
+            Err(e) => {
+                log::error!(
+                    "Failed to archive console log for job '{}' build #{}: {}",
+                    job_config.name,
+                    build.number,
+                    e
+                );
+                String::new()
+            }
+        };
+
+        let subject = format!(
+            "Jenkins Job Alert: {} failed a quality check (instance: {})",
+            job_config.name, instance.name
+        );
+
+        let issues_section = issues
+            .iter()
+            .map(|issue| format!("- {}\n", issue))
+            .collect::<String>();
+
+        let body = format!(
+            "Jenkins Monitor Alert\n\n\
+            Job: {}\n\
+            Instance: {}\n\
+            Status: Build requires attention\n\n\
+            Last Build: {} (Build #{})\n\
+            Build Result: {}\n\
+            Checked At: {}\n\n\
+            Issues:\n\
+            {}\n\
+            {}\
+            Please check Jenkins for issues.\n\n\
+            Jenkins URL: {}/job/{}",
+            job_config.name,
+            instance.name,
+            build.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+            build.number,
+            build.result.as_deref().unwrap_or("UNKNOWN"),
+            now.format("%Y-%m-%d %H:%M:%S UTC"),
+            issues_section,
+            console_section,
+            instance.url,
+            job_config.name
+        );
+
+        if self.notifiers.is_empty() {
+            log::warn!("No notifiers configured - alert would have been sent:");
+            log::warn!("Subject: {}", subject);
+            log::warn!("Body:\n{}", body);
+        }
+
+        for notifier in &self.notifiers {
+            if let Err(e) = notifier.notify(Severity::Warning, &subject, &body) {
+                log::error!(
+                    "Failed to deliver quality gate alert for job '{}': {}",
+                    job_config.name,
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Record a build reported by the webhook listener and, if it closes out
+    /// an open alert, send the same RESOLVED notification the polling path
+    /// would. This deliberately only handles the resolve path rather than
+    /// the full threshold/quality-gate evaluation in `check_job`: a webhook
+    /// notification carries just the completed build, not the cron schedule
+    /// context (`expected_run_time`, `daily_window`, etc.) needed to decide
+    /// whether a *new* alert should open, so that decision is left to the
+    /// next scheduled poll.
+    pub(crate) async fn handle_webhook_build(
+        &self,
+        instance_name: &str,
+        job_name: &str,
+        build: BuildDetails,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let job_config = self.config.job.iter().find(|j| j.name == job_name);
+        let instance = self.config.resolve_instance(Some(instance_name)).ok();
+
+        let resolved = {
+            let mut shared = self.shared.lock().await;
+            let state = shared
+                .job_states
+                .entry((instance_name.to_string(), job_name.to_string()))
+                .or_insert_with(|| JobState::new_at(now));
+
+            let previous_build_number = state.last_build_info.as_ref().map(|b| b.number);
+            let was_alerting = state.alerting;
+            let job_ran_again = Some(build.number) != previous_build_number;
+            let resolved = was_alerting && job_ran_again;
+
+            state.last_check = now;
+            state.last_build_info = Some(build.clone());
+            if resolved {
+                state.alerting = false;
+                state.alert_deferred_until = None;
+            }
+
+            let row = JobStateRow {
+                last_check: state.last_check,
+                last_build_info: state.last_build_info.clone(),
+                last_alert_sent: state.last_alert_sent,
+                alerting: state.alerting,
+                alert_deferred_until: state.alert_deferred_until,
+            };
+            shared.store.save(instance_name, job_name, &row)?;
+            shared.store.record_build(instance_name, job_name, &build)?;
+
+            resolved
+        };
+
+        if resolved {
+            match (job_config, instance) {
+                (Some(job_config), Some(instance)) => {
+                    self.send_resolved_notification(job_config, instance, &Some(build), now).await?;
+                }
+                _ => log::warn!(
+                    "Job '{}' on instance '{}' recovered via webhook, but no matching config entry was found - skipping resolved notification",
+                    job_name,
+                    instance_name
+                ),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Notify that a job which previously had an open alert has produced a
+    /// fresh build, closing out the alert lifecycle.
+    async fn send_resolved_notification(
+        &self,
+        job_config: &JobConfig,
+        instance: &JenkinsInstanceConfig,
+        last_build: &Option<BuildDetails>,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let build = match last_build {
+            Some(build) => build,
+            None => return Ok(()),
+        };
+
+        let subject = format!(
+            "RESOLVED: {} ran again at {} (build #{}, {}, instance: {})",
+            job_config.name,
+            now.format("%Y-%m-%d %H:%M:%S UTC"),
+            build.number,
+            build.result.as_deref().unwrap_or("UNKNOWN"),
+            instance.name
+        );
+
+        let body = format!(
+            "Jenkins Monitor Alert\n\n\
+            Job: {}\n\
+            Instance: {}\n\
+            Status: Recovered\n\n\
+            The job has produced a new build since its previous alert.\n\
+            Last Build: {} (Build #{})\n\
+            Build Result: {}\n\n\
+            Jenkins URL: {}/job/{}",
+            job_config.name,
+            instance.name,
+            build.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+            build.number,
+            build.result.as_deref().unwrap_or("UNKNOWN"),
+            instance.url,
+            job_config.name
+        );
+
+        log::info!("Job '{}' recovered - sending resolved notification", job_config.name);
+
+        for notifier in &self.notifiers {
+            if let Err(e) = notifier.notify(Severity::Info, &subject, &body) {
+                log::error!(
+                    "Failed to deliver resolved notification for job '{}': {}",
+                    job_config.name,
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The notification, if any, that a `check_job` evaluation decided is due.
+/// Carried back to the caller instead of being sent inline, so
+/// `check_jobs_concurrently` can deliver it as it joins each
+/// concurrently-spawned check, in dispatch order.
+enum PendingNotification {
+    None,
+    Overdue {
+        job_config: JobConfig,
+        instance_name: String,
+        current_build: Option<BuildDetails>,
+        expected_run_time: DateTime<Utc>,
+    },
+    QualityGate {
+        job_config: JobConfig,
+        instance_name: String,
+        current_build: Option<BuildDetails>,
+        quality_issues: Vec<String>,
+    },
+    Resolved {
+        job_config: JobConfig,
+        instance_name: String,
+        current_build: Option<BuildDetails>,
+    },
+}
+
+/// Min-heap entry pairing a job with the next instant it becomes due to be
+/// checked. `Ord` is reversed against `due_at` so a `BinaryHeap` (a max-heap
+/// by default) pops the earliest deadline first.
+struct ScheduledCheck {
+    due_at: DateTime<Utc>,
+    job: JobConfig,
+}
+
+impl PartialEq for ScheduledCheck {
+    fn eq(&self, other: &Self) -> bool {
+        self.due_at == other.due_at
+    }
+}
+
+impl Eq for ScheduledCheck {}
+
+impl PartialOrd for ScheduledCheck {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScheduledCheck {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.due_at.cmp(&self.due_at)
+    }
+}
+
+/// Find the next future time `cron_expr` is scheduled to fire, evaluating
+/// the schedule in `tz` so a local-time Jenkins cron doesn't misfire around
+/// DST transitions when compared against UTC.
+fn next_cron_occurrence(cron_expr: &str, tz: Tz) -> Result<DateTime<Utc>> {
+    use cron::Schedule;
+    use std::str::FromStr;
+
+    // Normalize here, in one place, so both a hand-written `config.toml`
+    // 5-field schedule and a spec fetched from `config.xml` (already
+    // normalized once in `jenkins::extract_schedule_from_config_xml`, but
+    // idempotent to re-run) parse the same way.
+    let schedule = Schedule::from_str(&crate::jenkins::normalize_cron_spec(cron_expr))?;
+    schedule
+        .upcoming(tz)
+        .next()
+        .map(|dt| dt.with_timezone(&Utc))
+        .with_context(|| format!("cron schedule '{}' has no upcoming occurrences", cron_expr))
+}
+
+/// Find the most recent time `cron_expr` was scheduled to fire at or before
+/// `now`, evaluating the schedule in `tz` so a local-time Jenkins cron
+/// doesn't misfire around DST transitions when compared against UTC.
+/// Exposed standalone (rather than only as a method) so the `list-jobs` CLI
+/// command can preview schedules without standing up a full `Monitor`.
+pub fn calculate_expected_run_time(cron_expr: &str, now: DateTime<Utc>, tz: Tz) -> Result<DateTime<Utc>> {
+    use cron::Schedule;
+    use std::str::FromStr;
+
+    // Normalize here, in one place, so both a hand-written `config.toml`
+    // 5-field schedule and a spec fetched from `config.xml` (already
+    // normalized once in `jenkins::extract_schedule_from_config_xml`, but
+    // idempotent to re-run) parse the same way.
+    let schedule = Schedule::from_str(&crate::jenkins::normalize_cron_spec(cron_expr))?;
+    let now_local = now.with_timezone(&tz);
+
+    // Find the most recent expected run time before now
+    let mut expected_time = now_local;
+    for upcoming in schedule.upcoming(tz).take(10) {
+        if upcoming > now_local {
+            break;
+        }
+        expected_time = upcoming;
+    }
+
+    // If we couldn't find a recent time, find the last time before now
+    if expected_time == now_local {
+        // Go back in time to find the last expected run
+        let past_time = now_local - Duration::days(7);
+        for upcoming in schedule.after(&past_time) {
+            if upcoming > now_local {
+                break;
+            }
+            expected_time = upcoming;
+        }
+    }
+
+    Ok(expected_time.with_timezone(&Utc))
+}
+
+/// Check whether `timestamp`, converted to `tz`, falls within a daily window
+/// of the form `"HH:MM-HH:MM"`. A window whose end is earlier than its start
+/// (e.g. `"22:00-02:00"`) is treated as crossing midnight.
+fn build_within_daily_window(timestamp: DateTime<Utc>, tz: Tz, window: &str) -> Result<bool> {
+    let (start, end) = parse_daily_window(window)?;
+    let local_time = timestamp.with_timezone(&tz).time();
+
+    if start <= end {
+        Ok(local_time >= start && local_time <= end)
+    } else {
+        Ok(local_time >= start || local_time <= end)
+    }
+}
+
+/// Check whether `now`, converted to `tz`, falls on a weekday and within a
+/// `"HH:MM-HH:MM"` working-hours window.
+fn is_within_working_hours(now: DateTime<Utc>, tz: Tz, window: &str) -> Result<bool> {
+    use chrono::Datelike;
+
+    let local = now.with_timezone(&tz);
+    if matches!(local.weekday(), chrono::Weekday::Sat | chrono::Weekday::Sun) {
+        return Ok(false);
+    }
+
+    build_within_daily_window(now, tz, window)
+}
+
+/// Find the earliest future instant, on a weekday at the window's start
+/// time in `tz`, that a `working_hours` window next opens.
+fn next_working_window_start(now: DateTime<Utc>, tz: Tz, window: &str) -> Result<DateTime<Utc>> {
+    use chrono::{Datelike, LocalResult, TimeZone};
+
+    let (start, _end) = parse_daily_window(window)?;
+    let local_now = now.with_timezone(&tz);
+
+    for days_ahead in 0i64..8 {
+        let candidate_date = local_now.date_naive() + Duration::days(days_ahead);
+        let candidate_naive = candidate_date.and_time(start);
+        let candidate = match tz.from_local_datetime(&candidate_naive) {
+            LocalResult::Single(dt) => dt,
+            LocalResult::Ambiguous(dt, _) => dt,
+            LocalResult::None => continue,
+        };
+
+        if candidate <= local_now {
+            continue;
+        }
+        if matches!(candidate.weekday(), chrono::Weekday::Sat | chrono::Weekday::Sun) {
+            continue;
+        }
+
+        return Ok(candidate.with_timezone(&Utc));
+    }
+
+    anyhow::bail!("could not find next working-hours window start for '{}'", window)
+}
+
+fn parse_daily_window(window: &str) -> Result<(chrono::NaiveTime, chrono::NaiveTime)> {
+    let (start, end) = window
+        .split_once('-')
+        .with_context(|| format!("daily_window '{}' must be formatted as \"HH:MM-HH:MM\"", window))?;
+
+    let start = chrono::NaiveTime::parse_from_str(start.trim(), "%H:%M")
+        .with_context(|| format!("invalid start time in daily_window '{}'", window))?;
+    let end = chrono::NaiveTime::parse_from_str(end.trim(), "%H:%M")
+        .with_context(|| format!("invalid end time in daily_window '{}'", window))?;
+
+    Ok((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn utc_ymd_hms(y: i32, mo: u32, d: u32, h: u32, mi: u32, s: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, s).unwrap()
+    }
+
+    #[test]
+    fn calculate_expected_run_time_finds_most_recent_occurrence_before_now() {
+        let now = utc_ymd_hms(2024, 1, 15, 15, 30, 0);
+        let expected = calculate_expected_run_time("0 0 12 * * *", now, chrono_tz::UTC).unwrap();
+        assert_eq!(expected, utc_ymd_hms(2024, 1, 15, 12, 0, 0));
+    }
+
+    #[test]
+    fn calculate_expected_run_time_falls_back_across_days_when_due_earlier() {
+        let now = utc_ymd_hms(2024, 1, 15, 2, 0, 0);
+        let expected = calculate_expected_run_time("0 0 12 * * *", now, chrono_tz::UTC).unwrap();
+        assert_eq!(expected, utc_ymd_hms(2024, 1, 14, 12, 0, 0));
+    }
+
+    #[test]
+    fn next_cron_occurrence_is_strictly_in_the_future() {
+        let before = Utc::now();
+        let next = next_cron_occurrence("0 * * * * *", chrono_tz::UTC).unwrap();
+        assert!(next > before, "expected {} to be after {}", next, before);
+    }
+
+    #[test]
+    fn build_within_daily_window_same_day_window() {
+        let window = "09:00-17:00";
+        let inside = utc_ymd_hms(2024, 1, 15, 12, 0, 0);
+        let outside = utc_ymd_hms(2024, 1, 15, 20, 0, 0);
+
+        assert!(build_within_daily_window(inside, chrono_tz::UTC, window).unwrap());
+        assert!(!build_within_daily_window(outside, chrono_tz::UTC, window).unwrap());
+    }
+
+    #[test]
+    fn build_within_daily_window_crosses_midnight() {
+        let window = "22:00-02:00";
+        let late_night = utc_ymd_hms(2024, 1, 15, 23, 0, 0);
+        let early_morning = utc_ymd_hms(2024, 1, 15, 1, 0, 0);
+        let midday = utc_ymd_hms(2024, 1, 15, 12, 0, 0);
+
+        assert!(build_within_daily_window(late_night, chrono_tz::UTC, window).unwrap());
+        assert!(build_within_daily_window(early_morning, chrono_tz::UTC, window).unwrap());
+        assert!(!build_within_daily_window(midday, chrono_tz::UTC, window).unwrap());
+    }
+
+    #[test]
+    fn is_within_working_hours_excludes_weekends() {
+        let window = "09:00-17:00";
+        // 2024-01-17 is a Wednesday, 2024-01-13 is a Saturday.
+        let weekday = utc_ymd_hms(2024, 1, 17, 10, 0, 0);
+        let weekend = utc_ymd_hms(2024, 1, 13, 10, 0, 0);
+
+        assert!(is_within_working_hours(weekday, chrono_tz::UTC, window).unwrap());
+        assert!(!is_within_working_hours(weekend, chrono_tz::UTC, window).unwrap());
+    }
+
+    #[test]
+    fn next_working_window_start_skips_weekend() {
+        let window = "09:00-17:00";
+        // 2024-01-12 is a Friday evening, after that day's window closed.
+        let friday_evening = utc_ymd_hms(2024, 1, 12, 18, 0, 0);
+        let next = next_working_window_start(friday_evening, chrono_tz::UTC, window).unwrap();
+        // 2024-01-15 is the following Monday.
+        assert_eq!(next, utc_ymd_hms(2024, 1, 15, 9, 0, 0));
+    }
+
+    #[test]
+    fn next_working_window_start_from_within_weekend_also_lands_on_monday() {
+        let window = "09:00-17:00";
+        // 2024-01-13 is a Saturday.
+        let saturday = utc_ymd_hms(2024, 1, 13, 10, 0, 0);
+        let next = next_working_window_start(saturday, chrono_tz::UTC, window).unwrap();
+        assert_eq!(next, utc_ymd_hms(2024, 1, 15, 9, 0, 0));
+    }
 }
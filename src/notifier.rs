@@ -0,0 +1,199 @@
+use anyhow::{Context, Result};
+use hmac::{Hmac, Mac};
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::config::{DesktopConfig, EmailConfig, NotifierConfig, WebhookConfig};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How important an alert is. Lets a `[[notifier]]` entry opt out of noisy
+/// traffic (e.g. a Slack channel that only wants `critical`) while email
+/// still receives everything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    #[default]
+    Info,
+    Warning,
+    Critical,
+}
+
+/// A destination an alert can be delivered to. Implementations must be safe
+/// to hold behind `Box<dyn Notifier>` in a `Vec` built once at startup.
+pub trait Notifier: Send + Sync {
+    fn notify(&self, severity: Severity, subject: &str, body: &str) -> Result<()>;
+}
+
+/// Build the configured set of notifiers from `[[notifier]]` entries.
+pub fn build_notifiers(configs: &[NotifierConfig]) -> Vec<Box<dyn Notifier>> {
+    configs
+        .iter()
+        .map(|c| -> Box<dyn Notifier> {
+            match c {
+                NotifierConfig::Email(email) => Box::new(EmailNotifier::new(email.clone())),
+                NotifierConfig::Webhook(webhook) => Box::new(WebhookNotifier::new(webhook.clone())),
+                NotifierConfig::Desktop(desktop) => Box::new(DesktopNotifier::new(desktop.clone())),
+            }
+        })
+        .collect()
+}
+
+pub struct EmailNotifier {
+    config: EmailConfig,
+}
+
+impl EmailNotifier {
+    pub fn new(config: EmailConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Notifier for EmailNotifier {
+    fn notify(&self, severity: Severity, subject: &str, body: &str) -> Result<()> {
+        if severity < self.config.min_severity {
+            log::debug!("Suppressing email alert below configured min_severity: {}", subject);
+            return Ok(());
+        }
+
+        log::info!("Sending email alert: {}", subject);
+
+        let mut message_builder = Message::builder()
+            .from(self.config.from.parse().context("Invalid 'from' email address")?)
+            .subject(subject);
+
+        // Add all recipients
+        for to_addr in &self.config.to {
+            message_builder = message_builder.to(to_addr.parse().context("Invalid 'to' email address")?);
+        }
+
+        let message = message_builder
+            .header(ContentType::TEXT_PLAIN)
+            .body(body.to_string())
+            .context("Failed to build email message")?;
+
+        // Create SMTP transport
+        let mut mailer_builder = SmtpTransport::relay(&self.config.smtp_host)
+            .context("Failed to create SMTP transport")?;
+
+        // Add credentials if provided
+        if let (Some(username), Some(password)) = (&self.config.username, &self.config.password) {
+            mailer_builder = mailer_builder.credentials(Credentials::new(
+                username.to_string(),
+                password.to_string(),
+            ));
+        }
+
+        let mailer = mailer_builder.build();
+
+        mailer
+            .send(&message)
+            .context("Failed to send email")?;
+
+        log::info!("Email alert sent successfully");
+        Ok(())
+    }
+}
+
+/// Posts a generic JSON payload to a webhook URL, e.g. a Slack/Discord/
+/// Mattermost incoming webhook. Most chat incoming-webhooks accept `{"text":
+/// "..."}` as a minimal payload, so `text` carries the subject folded into
+/// the body, since these integrations don't have a separate subject line;
+/// `subject` and `body` are also sent as separate fields.
+pub struct WebhookNotifier {
+    config: WebhookConfig,
+    client: reqwest::blocking::Client,
+}
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    text: String,
+    subject: &'a str,
+    body: &'a str,
+}
+
+impl WebhookNotifier {
+    pub fn new(config: WebhookConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::blocking::Client::new(),
+        }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify(&self, severity: Severity, subject: &str, body: &str) -> Result<()> {
+        if severity < self.config.min_severity {
+            log::debug!("Suppressing webhook alert below configured min_severity: {}", subject);
+            return Ok(());
+        }
+
+        log::info!("Posting webhook alert to {}", self.config.url);
+
+        let payload = WebhookPayload {
+            text: format!("{}\n\n{}", subject, body),
+            subject,
+            body,
+        };
+        let payload_bytes = serde_json::to_vec(&payload).context("Failed to serialize webhook payload")?;
+
+        let mut request = self
+            .client
+            .post(&self.config.url)
+            .header("Content-Type", "application/json");
+
+        if let Some(secret) = &self.config.secret {
+            let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+                .context("Webhook notifier secret has invalid length for HMAC-SHA256")?;
+            mac.update(&payload_bytes);
+            let signature = hex::encode(mac.finalize().into_bytes());
+            request = request.header("X-Signature", format!("sha256={}", signature));
+        }
+
+        let response = request
+            .body(payload_bytes)
+            .send()
+            .context("Failed to POST webhook notification")?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Webhook endpoint returned error status {}", response.status());
+        }
+
+        log::info!("Webhook alert sent successfully");
+        Ok(())
+    }
+}
+
+/// Shows a native desktop notification on the machine running the monitor.
+/// Intended for interactive/desktop use rather than headless servers.
+pub struct DesktopNotifier {
+    config: DesktopConfig,
+}
+
+impl DesktopNotifier {
+    pub fn new(config: DesktopConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Notifier for DesktopNotifier {
+    fn notify(&self, severity: Severity, subject: &str, body: &str) -> Result<()> {
+        if severity < self.config.min_severity {
+            log::debug!("Suppressing desktop notification below configured min_severity: {}", subject);
+            return Ok(());
+        }
+
+        log::info!("Showing desktop notification: {}", subject);
+
+        notify_rust::Notification::new()
+            .summary(subject)
+            .body(body)
+            .show()
+            .context("Failed to show desktop notification")?;
+
+        Ok(())
+    }
+}
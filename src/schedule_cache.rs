@@ -0,0 +1,82 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::backend::{BuildDetails, BuildHandle, CIBackend};
+
+struct CachedSchedule {
+    spec: Option<String>,
+    fetched_at: Instant,
+}
+
+/// Wraps a `CIBackend` and memoizes `job_schedule` lookups per job for
+/// `ttl`, so a cron spec that rarely changes doesn't get refetched on every
+/// scheduling evaluation. If a refresh turns up a different spec than what
+/// was cached, the new value simply replaces it, so schedule edits still
+/// propagate within one TTL window.
+pub struct CachingBackend {
+    inner: Box<dyn CIBackend>,
+    ttl: Duration,
+    cache: Mutex<HashMap<String, CachedSchedule>>,
+}
+
+impl CachingBackend {
+    pub fn new(inner: Box<dyn CIBackend>, ttl: Duration) -> Self {
+        Self {
+            inner,
+            ttl,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl CIBackend for CachingBackend {
+    async fn test_connection(&self) -> Result<()> {
+        self.inner.test_connection().await
+    }
+
+    async fn last_build_handle(&self, job: &str) -> Result<Option<BuildHandle>> {
+        self.inner.last_build_handle(job).await
+    }
+
+    async fn build_details(&self, handle: &BuildHandle) -> Result<BuildDetails> {
+        self.inner.build_details(handle).await
+    }
+
+    async fn job_schedule(&self, job: &str) -> Result<Option<String>> {
+        if let Some(cached) = self.cache.lock().unwrap().get(job) {
+            if cached.fetched_at.elapsed() < self.ttl {
+                return Ok(cached.spec.clone());
+            }
+        }
+
+        let spec = self.inner.job_schedule(job).await?;
+
+        let mut cache = self.cache.lock().unwrap();
+        if let Some(previous) = cache.get(job) {
+            if previous.spec != spec {
+                log::info!("Schedule for job '{}' changed, refreshing cached value", job);
+            }
+        }
+        cache.insert(
+            job.to_string(),
+            CachedSchedule {
+                spec: spec.clone(),
+                fetched_at: Instant::now(),
+            },
+        );
+
+        Ok(spec)
+    }
+
+    async fn console_log(&self, job: &str, build_number: u64) -> Result<String> {
+        self.inner.console_log(job, build_number).await
+    }
+
+    fn console_url(&self, job: &str, build_number: u64) -> String {
+        self.inner.console_url(job, build_number)
+    }
+}
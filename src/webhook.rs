@@ -0,0 +1,135 @@
+use anyhow::{Context, Result};
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use std::sync::Arc;
+
+use crate::backend::BuildDetails;
+use crate::config::WebhookServerConfig;
+use crate::monitor::Monitor;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Body of a Jenkins notification-plugin POST, trimmed to the fields the
+/// scheduler actually needs. `instance` must match the name of one of the
+/// configured `[[jenkins]]` entries.
+#[derive(Deserialize, Debug)]
+struct BuildNotification {
+    instance: String,
+    job: String,
+    build_number: u64,
+    timestamp: i64,
+    result: Option<String>,
+    duration_millis: Option<i64>,
+}
+
+#[derive(Clone)]
+struct WebhookState {
+    secret: String,
+    monitor: Arc<Monitor>,
+}
+
+/// Run the push-based ingestion listener until the process exits. Each
+/// request's body is verified against `X-Hub-Signature-256: sha256=<hmac>`
+/// before it's trusted.
+pub async fn serve(config: WebhookServerConfig, monitor: Arc<Monitor>) -> Result<()> {
+    let state = WebhookState {
+        secret: config.secret,
+        monitor,
+    };
+
+    let app = Router::new()
+        .route("/webhook/build", post(handle_build_notification))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(&config.bind_addr)
+        .await
+        .with_context(|| format!("Failed to bind webhook listener on {}", config.bind_addr))?;
+
+    log::info!("Webhook listener bound to {}", config.bind_addr);
+
+    axum::serve(listener, app)
+        .await
+        .context("Webhook listener failed")?;
+
+    Ok(())
+}
+
+async fn handle_build_notification(
+    State(state): State<WebhookState>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> StatusCode {
+    if let Err(status) = verify_signature(&state.secret, &headers, &body) {
+        return status;
+    }
+
+    let notification: BuildNotification = match serde_json::from_slice(&body) {
+        Ok(n) => n,
+        Err(e) => {
+            log::warn!("Failed to parse webhook build notification: {}", e);
+            return StatusCode::BAD_REQUEST;
+        }
+    };
+
+    let now = Utc::now();
+    let build_time = chrono::DateTime::from_timestamp_millis(notification.timestamp).unwrap_or(now);
+
+    let build = BuildDetails {
+        number: notification.build_number,
+        timestamp: build_time,
+        result: notification.result,
+        duration_millis: notification.duration_millis,
+    };
+
+    if let Err(e) = state
+        .monitor
+        .handle_webhook_build(&notification.instance, &notification.job, build, now)
+        .await
+    {
+        log::error!(
+            "Failed to record webhook-driven build for '{}/{}': {}",
+            notification.instance,
+            notification.job,
+            e
+        );
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    log::info!(
+        "Recorded build #{} for job '{}' on instance '{}' via webhook",
+        notification.build_number,
+        notification.job,
+        notification.instance
+    );
+
+    StatusCode::OK
+}
+
+/// Verify `body` against a `X-Hub-Signature-256: sha256=<hex hmac>` header
+/// using the configured shared secret. `Mac::verify_slice` compares in
+/// constant time, so this is safe against timing attacks.
+fn verify_signature(secret: &str, headers: &HeaderMap, body: &[u8]) -> Result<(), StatusCode> {
+    let header_value = headers
+        .get("X-Hub-Signature-256")
+        .and_then(|v| v.to_str().ok())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let hex_signature = header_value.strip_prefix("sha256=").ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let expected = hex::decode(hex_signature).map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    mac.update(body);
+
+    mac.verify_slice(&expected).map_err(|_| {
+        log::warn!("Rejected webhook notification with invalid HMAC signature");
+        StatusCode::UNAUTHORIZED
+    })
+}